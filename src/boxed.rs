@@ -0,0 +1,228 @@
+use core::fmt::{self, Debug, Formatter};
+use core::mem::{self, MaybeUninit};
+use core::ops::{Deref, DerefMut};
+use core::pin::Pin;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::CppClass;
+
+/// The hooks that allocate and free the memory of [`CppBox`]es. Map them to C++'s
+/// `operator new` and `operator delete` to pass boxes across the language boundary.
+#[derive(Debug, Clone, Copy)]
+pub struct CppAllocator {
+    /// Allocates `size` bytes aligned to `align`, returning null if it can't.
+    pub allocate: unsafe extern "C" fn(size: usize, align: usize) -> *mut u8,
+    /// Frees memory returned by `allocate`. Like `operator delete`, it isn't told the size, since
+    /// an instance may be freed through a base.
+    pub deallocate: unsafe extern "C" fn(ptr: *mut u8),
+}
+
+/// The installed allocator, or null for the default.
+static ALLOCATOR: AtomicPtr<CppAllocator> = AtomicPtr::new(ptr::null_mut());
+
+/// Installs the allocator of [`CppBox`]es.
+///
+/// Without an allocator, boxes are allocated by Rust's global allocator if the `alloc` feature is
+/// enabled, and can't be allocated otherwise.
+///
+/// # Safety
+/// Boxes are freed by the allocator installed when they're dropped, so no box allocated by
+/// another allocator may be alive.
+pub unsafe fn set_allocator(allocator: &'static CppAllocator) {
+    ALLOCATOR.store(allocator as *const _ as *mut _, Ordering::Release);
+}
+
+/// Returns the installed allocator, falling back to the default.
+fn allocator() -> &'static CppAllocator {
+    let allocator = ALLOCATOR.load(Ordering::Acquire);
+    if !allocator.is_null() {
+        return unsafe { &*allocator };
+    }
+
+    #[cfg(feature = "alloc")]
+    {
+        &global::GLOBAL
+    }
+    #[cfg(not(feature = "alloc"))]
+    {
+        panic!("no allocator is installed for `CppBox`")
+    }
+}
+
+/// The default allocator, which allocates through Rust's global allocator.
+#[cfg(feature = "alloc")]
+mod global {
+    use alloc::alloc::{alloc, dealloc, Layout};
+    use core::mem;
+
+    use super::CppAllocator;
+
+    /// Precedes each allocation, so it can be freed without being told its layout.
+    struct Header {
+        base: *mut u8,
+        layout: Layout,
+    }
+
+    pub static GLOBAL: CppAllocator = CppAllocator {
+        allocate,
+        deallocate,
+    };
+
+    unsafe extern "C" fn allocate(size: usize, align: usize) -> *mut u8 {
+        let align = align.max(mem::align_of::<Header>());
+        let offset = mem::size_of::<Header>().next_multiple_of(align);
+        let Some(layout) = offset
+            .checked_add(size)
+            .and_then(|size| Layout::from_size_align(size, align).ok())
+        else {
+            return core::ptr::null_mut();
+        };
+
+        let base = alloc(layout);
+        if base.is_null() {
+            return base;
+        }
+        let ptr = base.add(offset);
+        ptr.cast::<Header>().sub(1).write(Header { base, layout });
+        ptr
+    }
+
+    unsafe extern "C" fn deallocate(ptr: *mut u8) {
+        let Header { base, layout } = ptr.cast::<Header>().sub(1).read();
+        dealloc(base, layout)
+    }
+}
+
+/// An owned instance of a class, allocated by the installed [`CppAllocator`]. Dropping it destroys
+/// the instance through the class's virtual destructor, if it has one, so ownership can be passed
+/// to and from C++ with [`CppBox::into_raw`] and [`CppBox::from_raw`].
+///
+/// A class declares its virtual destructor by marking a virtual with `#[destructor]`. The instance
+/// is gone once it returns, so it stays `unsafe` to call, even through the virtuals trait:
+///
+/// ```compile_fail,E0133
+/// use vtable_gen::cpp_class;
+///
+/// cpp_class! {
+///     #[gen_vtable(no_unimpl)]
+///     struct Resource {
+///         virtual #[destructor] fn destroy(&mut self)
+///     }
+///
+///     impl Resource {
+///         fn new() -> Self {
+///             Self {}
+///         }
+///     }
+/// }
+///
+/// impl ResourceVirtuals for Resource {
+///     unsafe extern "C" fn destroy(this: &mut Resource) {
+///         unsafe { core::ptr::drop_in_place(this) }
+///     }
+/// }
+///
+/// let mut resource = Resource::new();
+/// <Resource as ResourceVirtuals>::destroy(&mut resource);
+/// ```
+pub struct CppBox<T: CppClass> {
+    ptr: NonNull<T>,
+}
+
+unsafe impl<T: CppClass + Send> Send for CppBox<T> {}
+unsafe impl<T: CppClass + Sync> Sync for CppBox<T> {}
+
+impl<T: CppClass> CppBox<T> {
+    /// Moves an instance into a new box.
+    pub fn new(value: T) -> Self {
+        unsafe { Self::new_in_place(|slot| _ = (*slot).write(value)) }
+    }
+
+    /// Constructs an instance directly in a new box, like with the class's `construct_at`.
+    ///
+    /// # Safety
+    /// `construct` must initialize the instance.
+    pub unsafe fn new_in_place(construct: impl FnOnce(*mut MaybeUninit<T>)) -> Self {
+        let ptr = (allocator().allocate)(mem::size_of::<T>(), mem::align_of::<T>());
+        let Some(ptr) = NonNull::new(ptr.cast::<T>()) else {
+            panic!("failed to allocate a `CppBox`");
+        };
+
+        // the allocation is freed if the construction panics
+        let guard = DeallocateOnUnwind(ptr.as_ptr().cast());
+        construct(ptr.as_ptr().cast());
+        mem::forget(guard);
+        Self { ptr }
+    }
+
+    /// Consumes the box, returning the instance it owns. The instance must be passed back to
+    /// [`CppBox::from_raw`] to be freed, or destroyed and freed by the installed allocator.
+    pub fn into_raw(this: Self) -> *mut T {
+        let ptr = this.ptr.as_ptr();
+        mem::forget(this);
+        ptr
+    }
+
+    /// Takes ownership of an instance.
+    ///
+    /// # Safety
+    /// `ptr` must be a pointer returned by the installed allocator's `allocate` to an initialized
+    /// instance, and not be owned by anything else. If it's an instance of a derived class, `T`
+    /// must have a virtual destructor to destroy it, and be the class or one of its primary bases,
+    /// which start with it. A secondary base lies inside the instance, so a pointer to one is never
+    /// accepted, even after casting it.
+    pub unsafe fn from_raw(ptr: *mut T) -> Self {
+        Self {
+            ptr: NonNull::new_unchecked(ptr),
+        }
+    }
+
+    /// Returns a pointer to the instance.
+    pub fn as_ptr(this: &Self) -> *const T {
+        this.ptr.as_ptr()
+    }
+
+    /// Returns a pinned reference to the instance, which never moves.
+    pub fn as_pin_mut(this: &mut Self) -> Pin<&mut T> {
+        unsafe { Pin::new_unchecked(this.ptr.as_mut()) }
+    }
+}
+
+impl<T: CppClass> Deref for CppBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: CppClass + Unpin> DerefMut for CppBox<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T: CppClass + Debug> Debug for CppBox<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: CppClass> Drop for CppBox<T> {
+    fn drop(&mut self) {
+        unsafe {
+            T::destruct(self.ptr.as_ptr());
+            (allocator().deallocate)(self.ptr.as_ptr().cast());
+        }
+    }
+}
+
+/// Frees an allocation of the installed allocator when dropped, unless it's forgotten.
+struct DeallocateOnUnwind(*mut u8);
+
+impl Drop for DeallocateOnUnwind {
+    fn drop(&mut self) {
+        unsafe { (allocator().deallocate)(self.0) }
+    }
+}
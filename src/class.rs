@@ -0,0 +1,28 @@
+/// A class with a generated vtable.
+pub trait CppClass {
+    /// The type of the class's primary vtable.
+    type VTable: 'static;
+
+    /// Returns the primary vtable installed in instances of the class.
+    fn vtable() -> &'static Self::VTable;
+
+    /// Destroys an instance in place through the class's virtual destructor, or drops it if the
+    /// class doesn't have one.
+    ///
+    /// # Safety
+    /// `this` must point to an initialized instance, which is left uninitialized.
+    unsafe fn destruct(this: *mut Self)
+    where
+        Self: Sized,
+    {
+        core::ptr::drop_in_place(this)
+    }
+}
+
+/// Names the primary vtable of a class through the class, so that classes deriving from it find
+/// the vtable wherever the class itself is in scope.
+#[doc(hidden)]
+pub trait HasVTable {
+    /// The type of the class's primary vtable.
+    type VTable;
+}
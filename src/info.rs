@@ -0,0 +1,77 @@
+use core::iter;
+
+/// Reflection metadata for a class.
+#[derive(Debug)]
+pub struct ClassInfo {
+    /// The name of the class.
+    pub name: &'static str,
+    /// The size of the class, including its bases.
+    pub size: usize,
+    /// The direct bases of the class. The primary base comes first.
+    pub bases: &'static [BaseInfo],
+    /// The slots the class declares in its primary vtable.
+    pub slots: &'static [SlotInfo],
+    /// Returns the class's own primary vtable, if the class generates one.
+    pub vtable: Option<fn() -> *const ()>,
+}
+
+/// Reflection metadata for a base of a class.
+#[derive(Debug)]
+pub struct BaseInfo {
+    /// The metadata of the base class.
+    pub info: &'static ClassInfo,
+    /// The offset of the base within the class.
+    pub offset: usize,
+}
+
+/// Reflection metadata for a vtable slot.
+#[derive(Debug)]
+pub struct SlotInfo {
+    /// The name of the virtual.
+    pub name: &'static str,
+    /// The index of the slot within the primary vtable.
+    pub index: usize,
+    /// The ABI of the virtual.
+    pub abi: &'static str,
+    /// The signature of the virtual.
+    pub signature: &'static str,
+}
+
+impl ClassInfo {
+    /// Returns the class and each of its primary bases, starting with the class itself.
+    pub fn primary_chain(&'static self) -> impl Iterator<Item = &'static ClassInfo> {
+        iter::successors(Some(self), |info| info.bases.first().map(|base| base.info))
+    }
+
+    /// Returns every slot in the class's primary vtable, including inherited ones, in the order
+    /// of their indices.
+    pub fn vtable_slots(&'static self) -> impl Iterator<Item = &'static SlotInfo> {
+        let slots = move || self.primary_chain().flat_map(|info| info.slots);
+        let len = slots().map(|slot| slot.index + 1).max().unwrap_or_default();
+        (0..len).filter_map(move |index| slots().find(|slot| slot.index == index))
+    }
+
+    /// Returns the slots of `vtable` whose functions differ from those of the class that
+    /// declares the slot.
+    ///
+    /// # Safety
+    /// `vtable` must be the primary vtable of an instance of the class.
+    pub unsafe fn overridden_slots(
+        &'static self,
+        vtable: *const (),
+    ) -> impl Iterator<Item = &'static SlotInfo> {
+        self.primary_chain()
+            .filter_map(|info| Some((info, info.vtable?())))
+            .flat_map(move |(info, declared)| {
+                info.slots.iter().filter(move |slot| {
+                    // SAFETY: both vtables contain the slots of the declaring class
+                    unsafe { read_slot(vtable, slot.index) != read_slot(declared, slot.index) }
+                })
+            })
+    }
+}
+
+/// Reads the function pointer in a vtable slot.
+unsafe fn read_slot(vtable: *const (), index: usize) -> usize {
+    *(vtable as *const usize).add(index)
+}
@@ -12,14 +12,14 @@
 //! ## Base Structs
 //! - Define a structure that contains virtual functions
 //! - Define a structure for the VTable that exactly matches the name of the structure it belongs to,
-//! followed by `VTable` exactly. Example:
+//!   followed by `VTable` exactly. Example:
 //! ```rs
 //! struct Foo {}
 //! struct FooVTable {}
 //! ```
 //! - Mark both the VTable and structure with `#[gen_vtable]`. Any function pointers you include in
-//! the VTable struct will require implementation in an automatically-generated `<name>Virtuals` trait.
-//! Complete Example:
+//!   the VTable struct will require implementation in an automatically-generated `<name>Virtuals` trait.
+//!   Complete Example:
 //! ```rs
 //! #[gen_vtable]
 //! struct Foo {}
@@ -133,18 +133,95 @@
 //! // `FooVirtuals` is implemented for `Foo`
 //! ```
 //!
+//! # `no_std`
+//!
+//! Generated code only names items through `::core`, so classes can be declared in `#![no_std]`
+//! crates by disabling the default features. The `alloc` feature enables the vtables of generic
+//! classes, which are allocated on first use, and the boxed constructors of `#[pinned]` classes.
+//! The `std` feature (enabled by default) enables the panic policies of `on_panic`, which need to
+//! catch unwinding panics. Without `alloc`, `CppBox`es can only be allocated once an allocator
+//! is installed with `set_allocator`.
+//!
+//! ```toml
+//! vtable_gen = { version = "1", default-features = false, features = ["alloc"] }
+//! ```
+//!
 //! # Known Limitations
-//! - `vtable_gen` currently does not support generic structs. This is a trivial addition, however, and
-//! will likely be added in the future
+//! - The vtables of generic classes are looked up by `TypeId` at runtime, so they need the `alloc`
+//!   feature, and classes can only be constructed when their type parameters are `'static`
+//! - Every base must itself be declared with `cpp_class!`; non-virtual bases are not supported
+//! - Virtuals must take `&self` or `&mut self`, and can't have type parameters of their own
 
-use proc_macro::TokenStream;
+#![no_std]
 
-mod class;
-mod parse;
-mod util;
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub use vtable_gen_macros::cpp_class;
 
-/// Generates a VTable member for a struct, or a trait if the struct is a vtable. See examples.
-#[proc_macro]
-pub fn cpp_class(input: TokenStream) -> TokenStream {
-    class::cpp_class_impl(input)
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub use alloc::boxed::Box;
+pub use boxed::{set_allocator, CppAllocator, CppBox};
+pub use class::CppClass;
+#[doc(hidden)]
+pub use class::HasVTable;
+pub use info::{BaseInfo, ClassInfo, SlotInfo};
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub use panic::guard;
+#[cfg(feature = "std")]
+pub use panic::{AbortOnPanic, DefaultOnPanic, LogAndDefaultOnPanic, PanicPolicy};
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub use registry::VTableRegistry;
+#[doc(hidden)]
+pub use symbol::DebugSlot;
+pub use vfptr::VfPtr;
+
+/// Defines a macro named `$name` that behaves like [`cpp_class!`], with the classes' helper macros
+/// naming their items through `$path`. Classes can still override the path with `#[vtable_path]`.
+///
+/// ```ignore
+/// vtable_gen::define_cpp_class!(sdk_class, "crate::sdk");
+///
+/// sdk_class! {
+///     #[gen_vtable]
+///     pub struct Foo {
+///         virtual fn foo(&self) -> u32
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_cpp_class {
+    ($name:ident, $path:literal) => {
+        $crate::__define_cpp_class!(($) $name, $path);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_cpp_class {
+    (($d:tt) $name:ident, $path:literal) => {
+        macro_rules! $name {
+            ($d($d class:tt)*) => {
+                $crate::cpp_class! {
+                    #[vtable_path = $path]
+                    $d($d class)*
+                }
+            };
+        }
+    };
 }
+
+mod boxed;
+mod class;
+mod info;
+#[cfg(feature = "std")]
+mod panic;
+#[cfg(feature = "alloc")]
+mod registry;
+mod symbol;
+mod vfptr;
@@ -0,0 +1,61 @@
+use core::any::Any;
+use std::boxed::Box;
+use std::panic::{self, AssertUnwindSafe};
+use std::string::String;
+
+/// Decides what a vtable slot returns to its caller when its implementation panics, since a panic
+/// must never unwind through an `extern "C"` frame.
+pub trait PanicPolicy<R> {
+    /// Handles a panic raised by `class::method`.
+    fn on_panic(class: &'static str, method: &'static str, payload: Box<dyn Any + Send>) -> R;
+}
+
+/// Reports the panic and aborts the process.
+pub struct AbortOnPanic;
+
+/// Silently returns the default value of the return type.
+pub struct DefaultOnPanic;
+
+/// Reports the panic and returns the default value of the return type.
+pub struct LogAndDefaultOnPanic;
+
+impl<R> PanicPolicy<R> for AbortOnPanic {
+    fn on_panic(class: &'static str, method: &'static str, payload: Box<dyn Any + Send>) -> R {
+        report(class, method, &payload);
+        std::process::abort()
+    }
+}
+
+impl<R: Default> PanicPolicy<R> for DefaultOnPanic {
+    fn on_panic(_class: &'static str, _method: &'static str, _payload: Box<dyn Any + Send>) -> R {
+        R::default()
+    }
+}
+
+impl<R: Default> PanicPolicy<R> for LogAndDefaultOnPanic {
+    fn on_panic(class: &'static str, method: &'static str, payload: Box<dyn Any + Send>) -> R {
+        report(class, method, &payload);
+        R::default()
+    }
+}
+
+/// Runs the implementation of `class::method`, handing any panic to the policy `P`.
+#[doc(hidden)]
+pub fn guard<P: PanicPolicy<R>, R>(
+    class: &'static str,
+    method: &'static str,
+    f: impl FnOnce() -> R,
+) -> R {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|payload| P::on_panic(class, method, payload))
+}
+
+/// Prints the panic message along with the virtual that raised it.
+fn report(class: &str, method: &str, payload: &Box<dyn Any + Send>) {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>");
+    std::eprintln!("panic in virtual {class}::{method}: {message}");
+}
@@ -0,0 +1,117 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use core::any::TypeId;
+use core::ops::DerefMut;
+
+/// A registry of vtables for generic classes, which can't declare statics of their own.
+/// Each vtable is allocated once per monomorphization, so its address is stable.
+pub struct VTableRegistry {
+    #[cfg(feature = "std")]
+    vtables: std::sync::Mutex<BTreeMap<TypeId, usize>>,
+    #[cfg(not(feature = "std"))]
+    vtables: spin::SpinLock<BTreeMap<TypeId, usize>>,
+}
+
+impl VTableRegistry {
+    /// Creates an empty registry.
+    pub const fn new() -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            vtables: std::sync::Mutex::new(BTreeMap::new()),
+            #[cfg(not(feature = "std"))]
+            vtables: spin::SpinLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Returns the vtable of type `V` registered for the class `K`, initializing it with `init`
+    /// on first access.
+    pub fn get_or_insert<K: 'static, V: 'static>(&self, init: impl FnOnce() -> V) -> &'static V {
+        let mut vtables = self.lock();
+        let vtable = *vtables
+            .entry(TypeId::of::<(K, V)>())
+            .or_insert_with(|| Box::leak(Box::new(init())) as *const V as usize);
+
+        // SAFETY: entries are keyed by the vtable type and leaked, so they live forever
+        unsafe { &*(vtable as *const V) }
+    }
+
+    #[cfg(feature = "std")]
+    fn lock(&self) -> impl DerefMut<Target = BTreeMap<TypeId, usize>> + '_ {
+        self.vtables
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn lock(&self) -> impl DerefMut<Target = BTreeMap<TypeId, usize>> + '_ {
+        self.vtables.lock()
+    }
+}
+
+impl Default for VTableRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A spin lock for targets without `std`. The registry's lock is only contended while a vtable is
+/// first initialized.
+#[cfg(not(feature = "std"))]
+mod spin {
+    use core::cell::UnsafeCell;
+    use core::ops::{Deref, DerefMut};
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    pub struct SpinLock<T> {
+        locked: AtomicBool,
+        value: UnsafeCell<T>,
+    }
+
+    // SAFETY: `value` is only accessed while holding `locked`
+    unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+    impl<T> SpinLock<T> {
+        pub const fn new(value: T) -> Self {
+            Self {
+                locked: AtomicBool::new(false),
+                value: UnsafeCell::new(value),
+            }
+        }
+
+        pub fn lock(&self) -> SpinLockGuard<'_, T> {
+            while self
+                .locked
+                .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_err()
+            {
+                core::hint::spin_loop();
+            }
+            SpinLockGuard(self)
+        }
+    }
+
+    /// Releases the lock when dropped, even if the vtable's initializer panics.
+    pub struct SpinLockGuard<'a, T>(&'a SpinLock<T>);
+
+    impl<T> Deref for SpinLockGuard<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            // SAFETY: the lock is held until the guard is dropped
+            unsafe { &*self.0.value.get() }
+        }
+    }
+
+    impl<T> DerefMut for SpinLockGuard<'_, T> {
+        fn deref_mut(&mut self) -> &mut T {
+            // SAFETY: the lock is held until the guard is dropped
+            unsafe { &mut *self.0.value.get() }
+        }
+    }
+
+    impl<T> Drop for SpinLockGuard<'_, T> {
+        fn drop(&mut self) {
+            self.0.locked.store(false, Ordering::Release);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use core::fmt::{self, Debug, Formatter};
+
+/// A vtable slot, printed as its address and, when the dynamic linker knows it, its symbol name.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DebugSlot(pub *const ());
+
+impl Debug for DebugSlot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match resolve_symbol(self.0) {
+            Some((symbol, 0)) => write!(f, "{:p} ({symbol})", self.0),
+            Some((symbol, offset)) => write!(f, "{:p} ({symbol}+{offset:#x})", self.0),
+            None => write!(f, "{:p}", self.0),
+        }
+    }
+}
+
+/// Looks up the symbol containing `addr` and the offset of `addr` within it using `dladdr`.
+/// Unexported functions are only known by the nearest exported symbol before them, which the
+/// offset makes apparent.
+#[cfg(all(unix, feature = "std"))]
+fn resolve_symbol(addr: *const ()) -> Option<(&'static str, usize)> {
+    use core::ffi::{c_char, c_int, c_void, CStr};
+
+    #[repr(C)]
+    struct DlInfo {
+        dli_fname: *const c_char,
+        dli_fbase: *mut c_void,
+        dli_sname: *const c_char,
+        dli_saddr: *mut c_void,
+    }
+
+    #[cfg_attr(target_os = "linux", link(name = "dl"))]
+    extern "C" {
+        fn dladdr(addr: *const c_void, info: *mut DlInfo) -> c_int;
+    }
+
+    let mut info = DlInfo {
+        dli_fname: core::ptr::null(),
+        dli_fbase: core::ptr::null_mut(),
+        dli_sname: core::ptr::null(),
+        dli_saddr: core::ptr::null_mut(),
+    };
+
+    // SAFETY: `info` is a valid out-pointer, and `dli_sname` is a NUL-terminated string owned by
+    // the dynamic linker when non-null, which lives as long as the image containing the slot
+    unsafe {
+        if dladdr(addr as *const c_void, &mut info) == 0 || info.dli_sname.is_null() {
+            return None;
+        }
+        let offset = (addr as usize).checked_sub(info.dli_saddr as usize)?;
+        Some((CStr::from_ptr(info.dli_sname).to_str().ok()?, offset))
+    }
+}
+
+#[cfg(not(all(unix, feature = "std")))]
+fn resolve_symbol(_addr: *const ()) -> Option<(&'static str, usize)> {
+    None
+}
@@ -0,0 +1,64 @@
+use core::fmt::{self, Debug, Formatter};
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+use core::ptr::NonNull;
+
+/// The pointer to the primary vtable in a class. It behaves like `&'static V`, but doesn't require
+/// `V` to be `'static`, so classes can be declared with generics that borrow. Only the lookup of a
+/// class's own vtable requires its generics to be `'static`.
+#[repr(transparent)]
+pub struct VfPtr<V>(NonNull<V>);
+
+// SAFETY: the pointer is only ever read through, like a shared reference
+unsafe impl<V: Sync> Send for VfPtr<V> {}
+unsafe impl<V: Sync> Sync for VfPtr<V> {}
+
+impl<V> VfPtr<V> {
+    /// Points to a vtable.
+    pub const fn new(vtable: &'static V) -> Self {
+        // SAFETY: references are never null
+        Self(unsafe { NonNull::new_unchecked(vtable as *const V as *mut V) })
+    }
+
+    /// Returns the pointer to the vtable.
+    pub const fn as_ptr(self) -> *const V {
+        self.0.as_ptr()
+    }
+}
+
+impl<V> Deref for VfPtr<V> {
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        // SAFETY: the pointer was made from a `'static` reference
+        unsafe { self.0.as_ref() }
+    }
+}
+
+impl<V> Clone for VfPtr<V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for VfPtr<V> {}
+
+impl<V: PartialEq> PartialEq for VfPtr<V> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<V: Eq> Eq for VfPtr<V> {}
+
+impl<V: Hash> Hash for VfPtr<V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<V: Debug> Debug for VfPtr<V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
@@ -0,0 +1,105 @@
+use vtable_gen::cpp_class;
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl, abi = "system")]
+    struct A {
+        virtual fn a(&self) -> u32,
+        virtual extern "C" fn c(&self) -> u32
+    }
+}
+
+impl AVirtuals for A {
+    extern "system" fn a(_this: &A) -> u32 {
+        1
+    }
+
+    extern "C" fn c(_this: &A) -> u32 {
+        2
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct B: A {
+        virtual fn b(&self) -> u32
+    }
+}
+
+impl AVirtuals for B {
+    extern "system" fn a(_this: &A) -> u32 {
+        3
+    }
+
+    extern "C" fn c(_this: &A) -> u32 {
+        4
+    }
+}
+
+impl BVirtuals for B {
+    extern "system" fn b(_this: &B) -> u32 {
+        5
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl, abi = "C")]
+    struct C: B {
+        virtual fn d(&self) -> u32
+    }
+}
+
+impl AVirtuals for C {
+    extern "system" fn a(_this: &A) -> u32 {
+        6
+    }
+
+    extern "C" fn c(_this: &A) -> u32 {
+        7
+    }
+}
+
+impl BVirtuals for C {
+    extern "system" fn b(_this: &B) -> u32 {
+        8
+    }
+}
+
+impl CVirtuals for C {
+    extern "C" fn d(_this: &C) -> u32 {
+        9
+    }
+}
+
+#[test]
+fn inherited() {
+    let abis = C::CLASS_INFO
+        .vtable_slots()
+        .map(|slot| (slot.name, slot.abi))
+        .collect::<Vec<_>>();
+
+    assert!(abis.contains(&("a", "system")));
+    assert!(abis.contains(&("c", "C")));
+    assert!(abis.contains(&("b", "system")));
+    assert!(abis.contains(&("d", "C")));
+}
+
+#[test]
+fn default() {
+    let a = A::default();
+    assert_eq!(a.a(), 1);
+    assert_eq!(a.c(), 2);
+
+    let b = B::default();
+    assert_eq!(b.a(), 3);
+    assert_eq!(b.c(), 4);
+    assert_eq!(b.b(), 5);
+
+    let c = C::default();
+    assert_eq!(c.a(), 6);
+    assert_eq!(c.c(), 7);
+    assert_eq!(c.b(), 8);
+    assert_eq!(c.d(), 9);
+}
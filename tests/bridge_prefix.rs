@@ -0,0 +1,213 @@
+use vtable_gen::cpp_class;
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct A {
+        a: u32,
+
+        virtual fn value(&self) -> u32
+    }
+
+    impl A {
+        fn new(a: u32) -> Self {
+            Self { a }
+        }
+    }
+}
+
+impl AVirtuals for A {
+    extern "C" fn value(this: &A) -> u32 {
+        this.a
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct B {
+        b: u32,
+
+        virtual fn value(&self) -> u32,
+        virtual fn b(&self, add: u32) -> u32
+    }
+
+    impl B {
+        fn new(b: u32) -> Self {
+            Self { b }
+        }
+    }
+}
+
+impl BVirtuals for B {
+    extern "C" fn value(this: &B) -> u32 {
+        this.b
+    }
+
+    extern "C" fn b(this: &B, add: u32) -> u32 {
+        this.b + add
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[bridge_prefix(B)]
+    #[gen_vtable(no_unimpl)]
+    struct C: A, B {
+        c: u32,
+
+        virtual fn c(&self) -> u32
+    }
+
+    impl C {
+        fn new(a: u32, b: u32, c: u32) -> Self {
+            Self {
+                base_a: A::new(a),
+                base_b: B::new(b),
+                c
+            }
+        }
+    }
+}
+
+impl AVirtuals for C {
+    extern "C" fn value(this: &A) -> u32 {
+        this.a + 10
+    }
+}
+
+impl BVirtuals for C {
+    extern "C" fn value(this: &B) -> u32 {
+        this.b + 20
+    }
+
+    extern "C" fn b(this: &B, add: u32) -> u32 {
+        this.b + add + 20
+    }
+}
+
+impl CVirtuals for C {
+    extern "C" fn c(this: &C) -> u32 {
+        this.c
+    }
+}
+
+#[test]
+fn prefixed() {
+    let c = C::new(1, 2, 3);
+
+    // the primary base is reached through `Deref`
+    assert_eq!(c.value(), 11);
+    // the secondary base's virtuals are prefixed
+    assert_eq!(c.b_value(), 22);
+    assert_eq!(c.b_b(1), 23);
+    assert_eq!(c.c(), 3);
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct D: A, B {}
+
+    impl D {
+        fn new(a: u32, b: u32) -> Self {
+            Self {
+                base_a: A::new(a),
+                base_b: B::new(b),
+            }
+        }
+    }
+}
+
+impl AVirtuals for D {
+    extern "C" fn value(this: &A) -> u32 {
+        this.a + 30
+    }
+}
+
+impl BVirtuals for D {
+    extern "C" fn value(this: &B) -> u32 {
+        this.b + 40
+    }
+
+    extern "C" fn b(this: &B, add: u32) -> u32 {
+        this.b + add + 40
+    }
+}
+
+impl DVirtuals for D {}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct F {
+        f: u32,
+
+        virtual fn c(&self) -> u32
+    }
+}
+
+impl FVirtuals for F {
+    extern "C" fn c(this: &F) -> u32 {
+        this.f
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct E: C, F {}
+}
+
+impl AVirtuals for E {
+    extern "C" fn value(this: &A) -> u32 {
+        this.a
+    }
+}
+
+impl BVirtuals for E {
+    extern "C" fn value(this: &B) -> u32 {
+        this.b
+    }
+
+    extern "C" fn b(this: &B, add: u32) -> u32 {
+        this.b + add
+    }
+}
+
+impl CVirtuals for E {
+    extern "C" fn c(this: &C) -> u32 {
+        this.c + 50
+    }
+}
+
+impl FVirtuals for E {
+    extern "C" fn c(this: &F) -> u32 {
+        this.f + 60
+    }
+}
+
+impl EVirtuals for E {}
+
+#[test]
+fn collision() {
+    let d = D::new(1, 2);
+
+    // the primary base's virtual isn't hidden by the secondary base's
+    assert_eq!(d.value(), 31);
+    // so the secondary base's virtuals are prefixed
+    assert_eq!(d.b_value(), 42);
+    assert_eq!(d.b_b(1), 43);
+}
+
+#[test]
+fn inherited_collision() {
+    let e = E::default();
+
+    // `c` is reached through the primary base
+    assert_eq!(e.c(), 50);
+    assert_eq!(e.f_c(), 60);
+    // the prefixed bases of the primary base are still prefixed
+    assert_eq!(e.value(), 0);
+    assert_eq!(e.b_value(), 0);
+}
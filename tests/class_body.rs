@@ -0,0 +1,113 @@
+use std::mem;
+
+use vtable_gen::cpp_class;
+
+// members are declared in any order, as in a C++ header
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Shape {
+        pub static MAX_SIDES: u32 = 12,
+
+        virtual fn area(&self) -> f32,
+        sides: u32,
+
+        fn new(sides: u32, scale: f32) -> Self {
+            Self {
+                sides: sides.min(Self::MAX_SIDES),
+                scale,
+            }
+        }
+
+        /// The perimeter of the shape.
+        virtual fn perimeter(&self) -> f32,
+        scale: f32,
+
+        fn is_polygon(&self) -> bool {
+            self.sides >= 3
+        }
+    }
+
+    impl Shape {
+        fn scaled(sides: u32, scale: f32) -> Self {
+            let mut shape = Self::new(sides, 1.0);
+            shape.scale = scale;
+            shape
+        }
+    }
+}
+
+impl ShapeVirtuals for Shape {
+    extern "C" fn area(this: &Shape) -> f32 {
+        this.scale * this.scale
+    }
+
+    extern "C" fn perimeter(this: &Shape) -> f32 {
+        this.sides as f32 * this.scale
+    }
+}
+
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Square: Shape {
+        static SIDES: u32 = 4,
+
+        fn new(side: f32) -> Self {
+            Self {
+                base_shape: Shape::new(Self::SIDES, side),
+                filled: false,
+            }
+        }
+
+        virtual fn diagonal(&self) -> f32,
+        filled: bool,
+    }
+}
+
+impl ShapeVirtuals for Square {
+    extern "C" fn area(this: &Shape) -> f32 {
+        this.scale * this.scale
+    }
+
+    extern "C" fn perimeter(this: &Shape) -> f32 {
+        4.0 * this.scale
+    }
+}
+
+impl SquareVirtuals for Square {
+    extern "C" fn diagonal(this: &Square) -> f32 {
+        this.scale * 2f32.sqrt()
+    }
+}
+
+#[test]
+fn interleaved() {
+    let shape = Shape::new(3, 2.0);
+
+    assert_eq!(shape.area(), 4.0);
+    assert_eq!(shape.perimeter(), 6.0);
+    assert_eq!(Shape::scaled(20, 3.0).sides, Shape::MAX_SIDES);
+    assert_eq!(Shape::scaled(20, 3.0).area(), 9.0);
+}
+
+#[test]
+fn layout() {
+    assert_eq!(mem::offset_of!(Shape, sides), mem::size_of::<usize>());
+    assert!(mem::offset_of!(Shape, scale) > mem::offset_of!(Shape, sides));
+}
+
+#[test]
+fn non_virtuals() {
+    assert!(Shape::new(3, 1.0).is_polygon());
+    assert!(!Shape::new(2, 1.0).is_polygon());
+}
+
+#[test]
+fn derived() {
+    let square = Square::new(2.0);
+
+    assert_eq!(square.sides, 4);
+    assert_eq!(square.perimeter(), 8.0);
+    assert_eq!(square.diagonal(), 2.0 * 2f32.sqrt());
+    assert!(square.is_polygon());
+    assert!(!square.filled);
+}
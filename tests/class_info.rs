@@ -0,0 +1,145 @@
+use vtable_gen::cpp_class;
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct A {
+        a: u32,
+
+        virtual fn a(&self) -> u32,
+        virtual(2) fn b(&self, b: u32) -> u32
+    }
+}
+
+impl AVirtuals for A {
+    extern "C" fn a(this: &A) -> u32 {
+        this.a
+    }
+
+    extern "C" fn b(_this: &A, b: u32) -> u32 {
+        b
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct B: A {
+        c: u64,
+
+        virtual extern "system" fn c(&mut self) -> u64
+    }
+}
+
+impl AVirtuals for B {
+    extern "C" fn a(this: &A) -> u32 {
+        this.a + 1
+    }
+
+    extern "C" fn b(_this: &A, b: u32) -> u32 {
+        b + 1
+    }
+}
+
+impl BVirtuals for B {
+    extern "system" fn c(this: &mut B) -> u64 {
+        this.c
+    }
+}
+
+// the VTables of generic classes are registered, which needs `alloc`
+#[cfg(feature = "alloc")]
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct G<T: Default> {
+        g: T,
+
+        virtual fn g(&self) -> u32,
+        virtual fn wrapped(&self, first_argument: u64, second_argument: u64, third_argument: u64) -> u64
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Default> GVirtuals<T> for G<T> {
+    extern "C" fn g(_this: &G<T>) -> u32 {
+        0
+    }
+
+    extern "C" fn wrapped(
+        _this: &G<T>,
+        first_argument: u64,
+        second_argument: u64,
+        third_argument: u64,
+    ) -> u64 {
+        first_argument + second_argument + third_argument
+    }
+}
+
+// only `a` is overridden by `B`
+const PARTIAL_VTBL: BVTable = BVTable {
+    base_a: AVTable {
+        a: <B as AVirtuals>::a,
+        unimpl_1: A::VTBL_FOR_A.unimpl_1,
+        b: <A as AVirtuals>::b,
+    },
+    c: <B as BVirtuals>::c,
+};
+
+#[test]
+fn metadata() {
+    let info = &B::CLASS_INFO;
+    assert_eq!(info.name, "B");
+    assert_eq!(info.size, std::mem::size_of::<B>());
+    assert_eq!(info.bases.len(), 1);
+    assert_eq!(info.bases[0].info.name, "A");
+    assert_eq!(info.bases[0].offset, 0);
+
+    // the slot table covers inherited slots
+    let slots = info
+        .vtable_slots()
+        .map(|slot| (slot.name, slot.index, slot.abi))
+        .collect::<Vec<_>>();
+    assert_eq!(slots, [("a", 0, "C"), ("b", 2, "C"), ("c", 3, "system")]);
+    assert_eq!(
+        info.slots[0].signature,
+        "extern \"system\" fn c(this: &mut B) -> u64"
+    );
+    assert_eq!(
+        info.bases[0].info.slots[1].signature,
+        "extern \"C\" fn b(this: &A, b: u32) -> u32"
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn generic_metadata() {
+    let info = &G::<u64>::CLASS_INFO;
+    assert_eq!(info.name, "G");
+    assert_eq!(info.size, std::mem::size_of::<usize>() * 2);
+    // long signatures are printed on one line
+    assert_eq!(
+        info.slots[1].signature,
+        "extern \"C\" fn wrapped(this: &G<T>, first_argument: u64, second_argument: u64, third_argument: u64) -> u64"
+    );
+}
+
+#[test]
+fn overridden_slots() {
+    let a = A::default();
+    assert_eq!(a.overridden_slots().count(), 0);
+
+    let b = B::default();
+    let overridden = b
+        .overridden_slots()
+        .map(|slot| slot.name)
+        .collect::<Vec<_>>();
+    assert_eq!(overridden, ["a", "b"]);
+
+    let b = B::_default_with_vtable(&PARTIAL_VTBL);
+    let overridden = b
+        .overridden_slots()
+        .map(|slot| slot.name)
+        .collect::<Vec<_>>();
+    assert_eq!(overridden, ["a"]);
+}
@@ -0,0 +1,113 @@
+use vtable_gen::cpp_class;
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct Logger {
+        level: u8,
+        name: &'static str,
+        parent: Option<&'static Logger>,
+
+        virtual fn level(&self) -> u8
+    }
+
+    impl Logger {
+        const fn new(name: &'static str, level: u8) -> Self {
+            Self {
+                level,
+                name,
+                parent: None,
+            }
+        }
+    }
+}
+
+impl LoggerVirtuals for Logger {
+    extern "C" fn level(this: &Logger) -> u8 {
+        this.level
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct FileLogger: Logger {
+        fd: i32,
+        flags: (bool, char),
+
+        virtual fn fd(&self) -> i32
+    }
+
+    impl FileLogger {
+        const fn new(name: &'static str, fd: i32) -> Self {
+            Self {
+                base_logger: Logger::new(name, 2),
+                fd,
+                flags: (true, 'w'),
+            }
+        }
+    }
+}
+
+impl LoggerVirtuals for FileLogger {
+    extern "C" fn level(this: &Logger) -> u8 {
+        this.level + 1
+    }
+}
+
+impl FileLoggerVirtuals for FileLogger {
+    extern "C" fn fd(this: &FileLogger) -> i32 {
+        this.fd
+    }
+}
+
+// fields without a const default keep the default runtime-only
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct NamedLogger: Logger {
+        names: Vec<String>,
+    }
+}
+
+impl LoggerVirtuals for NamedLogger {
+    extern "C" fn level(_this: &Logger) -> u8 {
+        5
+    }
+}
+
+impl NamedLoggerVirtuals for NamedLogger {}
+
+static ROOT: Logger = Logger::new("root", 1);
+static FILE: FileLogger = FileLogger::new("file", 3);
+static DEFAULT: FileLogger = FileLogger::DEFAULT;
+
+#[test]
+fn statics() {
+    assert_eq!(ROOT.level(), 1);
+    assert_eq!(ROOT.name, "root");
+    assert_eq!(FILE.level(), 3);
+    assert_eq!(FILE.fd(), 3);
+    assert_eq!(FILE.flags, (true, 'w'));
+}
+
+#[test]
+fn const_default() {
+    assert_eq!(DEFAULT.level(), 1);
+    assert_eq!(DEFAULT.fd(), 0);
+    assert_eq!(DEFAULT.name, "");
+    assert!(DEFAULT.parent.is_none());
+    assert_eq!(DEFAULT.flags, (false, '\0'));
+    assert!(std::ptr::eq(
+        Logger::DEFAULT.vfptr.as_ptr(),
+        ROOT.vfptr.as_ptr()
+    ));
+}
+
+#[test]
+fn runtime_default() {
+    let logger = NamedLogger::default();
+
+    assert_eq!(logger.level(), 5);
+    assert!(logger.names.is_empty());
+}
@@ -0,0 +1,187 @@
+use std::mem::MaybeUninit;
+
+use vtable_gen::cpp_class;
+
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Widget {
+        id: u32,
+
+        virtual fn id(&self) -> u32
+    }
+
+    impl Widget {
+        fn new(id: u32) -> Self {
+            if id == 0 {
+                return Self { id: 1 };
+            }
+
+            match id {
+                1..=9 => Self { id },
+                id => Self { id: id * 10 },
+            }
+        }
+
+        fn root() -> Self {
+            Self::new(7)
+        }
+
+        fn offset(id: u32, offset: u32) -> Self {
+            // the instance is produced by a helper, so it's moved into place
+            shift(Self { id }, offset)
+        }
+    }
+}
+
+fn shift(mut widget: Widget, offset: u32) -> Widget {
+    widget.id += offset;
+    widget
+}
+
+impl WidgetVirtuals for Widget {
+    extern "C" fn id(this: &Widget) -> u32 {
+        this.id
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct Listener {
+        virtual fn listen(&self) -> u32
+    }
+}
+
+impl ListenerVirtuals for Listener {
+    extern "C" fn listen(_this: &Listener) -> u32 {
+        1
+    }
+}
+
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Button: Widget, Listener {
+        label: &'static str,
+
+        virtual fn label_len(&self) -> usize
+    }
+
+    impl Button {
+        fn new(id: u32, label: &'static str) -> Self {
+            Self {
+                base_widget: Widget::new(id),
+                base_listener: Listener::default(),
+                label,
+            }
+        }
+
+        fn offset(id: u32) -> Self {
+            Self {
+                base_widget: Widget::offset(id, 5),
+                base_listener: Default::default(),
+                label: "offset",
+            }
+        }
+    }
+}
+
+impl WidgetVirtuals for Button {
+    extern "C" fn id(this: &Widget) -> u32 {
+        this.id + 100
+    }
+}
+
+impl ListenerVirtuals for Button {
+    extern "C" fn listen(_this: &Listener) -> u32 {
+        2
+    }
+}
+
+impl ButtonVirtuals for Button {
+    extern "C" fn label_len(this: &Button) -> usize {
+        this.label.len()
+    }
+}
+
+// the arguments are free to share names with the slot
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct View {
+        ptr: usize,
+        slot: usize,
+
+        virtual fn sum(&self) -> usize
+    }
+
+    impl View {
+        fn new(ptr: usize, slot: usize) -> Self { Self { ptr, slot } }
+    }
+}
+
+impl ViewVirtuals for View {
+    extern "C" fn sum(this: &View) -> usize {
+        this.ptr + this.slot
+    }
+}
+
+/// Constructs an instance in a buffer the way a host would.
+fn construct<T>(construct: impl FnOnce(*mut MaybeUninit<T>)) -> Box<T> {
+    let mut slot = Box::new(MaybeUninit::<T>::uninit());
+    construct(&mut *slot);
+    unsafe { Box::from_raw(Box::into_raw(slot).cast()) }
+}
+
+#[test]
+fn placed() {
+    let widget = construct(|ptr| unsafe { Widget::construct_at(ptr, 3) });
+    let big = construct(|ptr| unsafe { Widget::construct_at(ptr, 30) });
+    let zero = construct(|ptr| unsafe { Widget::construct_at(ptr, 0) });
+
+    assert_eq!(widget.id(), 3);
+    assert_eq!(big.id(), 300);
+    assert_eq!(zero.id(), 1);
+    assert!(std::ptr::eq(
+        widget.vfptr.as_ptr(),
+        Widget::vtbl_for_widget()
+    ));
+}
+
+#[test]
+fn delegated() {
+    let root = construct(|ptr| unsafe { Widget::construct_root_at(ptr) });
+
+    assert_eq!(root.id(), 7);
+}
+
+#[test]
+fn moved_into_place() {
+    let widget = construct(|ptr| unsafe { Widget::construct_offset_at(ptr, 3, 4) });
+
+    assert_eq!(widget.id(), 7);
+}
+
+#[test]
+fn derived() {
+    let button = construct(|ptr| unsafe { Button::construct_at(ptr, 4, "ok") });
+    let listener: &Listener = (*button).as_ref();
+
+    assert_eq!(button.id(), 104);
+    assert_eq!(button.label, "ok");
+    assert_eq!(button.label_len(), 2);
+    assert_eq!(listener.listen(), 2);
+    assert!(std::ptr::eq(
+        listener.vfptr.as_ptr(),
+        Button::vtbl_for_listener()
+    ));
+
+    let offset = construct(|ptr| unsafe { Button::construct_offset_at(ptr, 1) });
+    assert_eq!(offset.id(), 106);
+    assert_eq!(offset.label, "offset");
+}
+
+#[test]
+fn argument_names() {
+    let view = construct(|ptr| unsafe { View::construct_at(ptr, 1, 2) });
+
+    assert_eq!(view.sum(), 3);
+}
@@ -0,0 +1,276 @@
+use vtable_gen::cpp_class;
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct Counter {
+        count: u32,
+
+        virtual fn count(&self) -> u32
+    }
+
+    impl Counter {
+        fn new(count: u32) -> Self {
+            if count > 100 {
+                return Self { count: 100 };
+            }
+
+            match count {
+                0 => Self { count: 1 },
+                count => {
+                    let count = count * 2;
+                    Self { count }
+                }
+            }
+        }
+
+        fn with_offset(count: u32, offset: u32) -> Self {
+            // the instantiation is passed through a helper
+            offset_by(Self { count }, offset)
+        }
+    }
+}
+
+fn offset_by(mut counter: Counter, offset: u32) -> Counter {
+    counter.count += offset;
+    counter
+}
+
+impl Counter {
+    fn doubled(mut self) -> Self {
+        self.count *= 2;
+        self
+    }
+}
+
+impl CounterVirtuals for Counter {
+    extern "C" fn count(this: &Counter) -> u32 {
+        this.count
+    }
+}
+
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Timer: Counter {
+        ticks: u32,
+
+        virtual fn ticks(&self) -> u32
+    }
+
+    impl Timer {
+        fn new(ticks: u32, paused: bool) -> Self {
+            if paused {
+                Self {
+                    base_counter: Counter::new(0),
+                    ticks: 0,
+                }
+            } else {
+                Self {
+                    base_counter: Counter::new(ticks),
+                    ticks,
+                }
+            }
+        }
+    }
+}
+
+impl CounterVirtuals for Timer {
+    extern "C" fn count(this: &Counter) -> u32 {
+        this.count + 1000
+    }
+}
+
+impl TimerVirtuals for Timer {
+    extern "C" fn ticks(this: &Timer) -> u32 {
+        this.ticks
+    }
+}
+
+// bases can be initialized by any expression that produces them
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct Stopwatch: Counter {
+        laps: u32,
+        label: &'static str,
+
+        virtual fn laps(&self) -> u32
+    }
+
+    impl Stopwatch {
+        fn from_trait() -> Self {
+            Self {
+                base_counter: Default::default(),
+                laps: 1,
+                label: "trait",
+            }
+        }
+
+        fn from_local(count: u32) -> Self {
+            let counter = Counter::new(count);
+            Self {
+                base_counter: counter,
+                laps: 2,
+                label: "local",
+            }
+        }
+
+        fn from_builder(count: u32) -> Self {
+            Self {
+                base_counter: Counter::new(count).doubled(),
+                laps: 3,
+                label: "builder",
+            }
+        }
+
+        fn from_update(laps: u32) -> Self {
+            Self {
+                laps,
+                ..Default::default()
+            }
+        }
+    }
+}
+
+impl CounterVirtuals for Stopwatch {
+    extern "C" fn count(this: &Counter) -> u32 {
+        this.count + 2000
+    }
+}
+
+impl StopwatchVirtuals for Stopwatch {
+    extern "C" fn laps(this: &Stopwatch) -> u32 {
+        this.laps
+    }
+}
+
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Gauge {
+        level: u32,
+
+        virtual fn level(&self) -> u32
+    }
+
+    impl Gauge {
+        fn new(level: u32) -> Self {
+            Self { level }
+        }
+    }
+}
+
+// functions of the base that aren't its constructors produce it like any other expression
+impl From<u32> for Gauge {
+    fn from(level: u32) -> Self {
+        Self::new(level * 10)
+    }
+}
+
+impl Default for Gauge {
+    fn default() -> Self {
+        Self::new(7)
+    }
+}
+
+impl GaugeVirtuals for Gauge {
+    extern "C" fn level(this: &Gauge) -> u32 {
+        this.level
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct Meter: Gauge {
+        scale: u32,
+
+        virtual fn scale(&self) -> u32
+    }
+
+    impl Meter {
+        fn from_level(level: u32) -> Self {
+            Self {
+                base_gauge: Gauge::from(level),
+                scale: 1,
+            }
+        }
+
+        fn defaulted() -> Self {
+            Self {
+                base_gauge: Gauge::default(),
+                scale: 2,
+            }
+        }
+    }
+}
+
+impl GaugeVirtuals for Meter {
+    extern "C" fn level(this: &Gauge) -> u32 {
+        this.level + 3000
+    }
+}
+
+impl MeterVirtuals for Meter {
+    extern "C" fn scale(this: &Meter) -> u32 {
+        this.scale
+    }
+}
+
+#[test]
+fn early_return() {
+    assert_eq!(Counter::new(200).count(), 100);
+}
+
+#[test]
+fn match_arms() {
+    assert_eq!(Counter::new(0).count(), 1);
+    assert_eq!(Counter::new(3).count(), 6);
+}
+
+#[test]
+fn helper() {
+    assert_eq!(Counter::with_offset(3, 4).count(), 7);
+}
+
+#[test]
+fn derived_branches() {
+    let paused = Timer::new(5, true);
+    let running = Timer::new(5, false);
+
+    assert_eq!(paused.count(), 1001);
+    assert_eq!(paused.ticks(), 0);
+    assert_eq!(running.count(), 1010);
+    assert_eq!(running.ticks(), 5);
+}
+
+#[test]
+fn rebound_bases() {
+    let from_trait = Stopwatch::from_trait();
+    let from_local = Stopwatch::from_local(3);
+    let from_builder = Stopwatch::from_builder(3);
+    let from_update = Stopwatch::from_update(4);
+
+    assert_eq!(from_trait.count(), 2000);
+    assert_eq!(from_trait.laps(), 1);
+    assert_eq!(from_local.count(), 2006);
+    assert_eq!(from_local.laps(), 2);
+    assert_eq!(from_builder.count(), 2012);
+    assert_eq!(from_builder.laps(), 3);
+    assert_eq!(from_update.count(), 2000);
+    assert_eq!(from_update.laps(), 4);
+    assert_eq!(from_update.label, "");
+}
+
+#[test]
+fn base_functions() {
+    let from_level = Meter::from_level(2);
+    let defaulted = Meter::defaulted();
+    let derived_default = Meter::default();
+
+    assert_eq!(from_level.level(), 3020);
+    assert_eq!(from_level.scale(), 1);
+    assert_eq!(defaulted.level(), 3007);
+    assert_eq!(defaulted.scale(), 2);
+    assert_eq!(derived_default.level(), 3007);
+    assert_eq!(derived_default.scale(), 0);
+}
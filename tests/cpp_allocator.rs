@@ -0,0 +1,174 @@
+use std::alloc::{self, Layout};
+use std::panic;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use vtable_gen::{cpp_class, CppAllocator, CppBox};
+
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Shape {
+        virtual fn area(&self) -> u32,
+        virtual #[destructor] fn destroy(&mut self)
+    }
+
+    impl Shape {
+        fn new() -> Self {
+            Self {}
+        }
+    }
+}
+
+impl ShapeVirtuals for Shape {
+    extern "C" fn area(_this: &Shape) -> u32 {
+        0
+    }
+
+    unsafe extern "C" fn destroy(this: &mut Shape) {
+        unsafe { ptr::drop_in_place(this) }
+    }
+}
+
+// larger than its base, so freeing it with the base's size would be wrong
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Rect: Shape {
+        size: [u64; 4],
+    }
+
+    impl Rect {
+        fn new(width: u64, height: u64) -> Self {
+            Self {
+                base_shape: Shape::new(),
+                size: [width, height, 0, 0],
+            }
+        }
+    }
+}
+
+impl ShapeVirtuals for Rect {
+    extern "C" fn area(this: &Shape) -> u32 {
+        // `this` is borrowed through the whole instance, so it can be converted back
+        let [width, height, ..] = unsafe { Rect::from_base_shape(this) }.size;
+        (width * height) as u32
+    }
+
+    unsafe extern "C" fn destroy(this: &mut Shape) {
+        unsafe { ptr::drop_in_place(Rect::from_base_shape_mut(this)) }
+    }
+}
+
+impl RectVirtuals for Rect {}
+
+cpp_class! {
+    #[gen_vtable]
+    struct Tag {
+        tag: u64,
+
+        virtual fn tag(&self) -> u64
+    }
+
+    impl Tag {
+        fn new(tag: u64) -> Self {
+            Self { tag }
+        }
+    }
+}
+
+// the secondary base lies inside the instance, so only the primary base starts the allocation
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Label: Shape, Tag {
+        size: u64,
+    }
+
+    impl Label {
+        fn new(size: u64, tag: u64) -> Self {
+            Self {
+                base_shape: Shape::new(),
+                base_tag: Tag::new(tag),
+                size,
+            }
+        }
+    }
+}
+
+impl ShapeVirtuals for Label {
+    extern "C" fn area(this: &Shape) -> u32 {
+        unsafe { Label::from_base_shape(this) }.size as u32
+    }
+
+    unsafe extern "C" fn destroy(this: &mut Shape) {
+        unsafe { ptr::drop_in_place(Label::from_base_shape_mut(this)) }
+    }
+}
+
+impl TagVirtuals for Label {
+    extern "C" fn tag(this: &Tag) -> u64 {
+        this.tag
+    }
+}
+
+impl LabelVirtuals for Label {}
+
+/// The number of live allocations made by the test allocator.
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+/// The size of the header that stores the layout of an allocation, which frees it without being
+/// told its size, like `operator delete`.
+const HEADER: usize = 16;
+
+unsafe extern "C" fn allocate(size: usize, align: usize) -> *mut u8 {
+    LIVE.fetch_add(1, Ordering::Relaxed);
+    let layout = Layout::from_size_align(size + HEADER, align.max(HEADER)).unwrap();
+    let ptr = alloc::alloc(layout);
+    ptr.cast::<Layout>().write(layout);
+    ptr.add(HEADER)
+}
+
+unsafe extern "C" fn deallocate(ptr: *mut u8) {
+    LIVE.fetch_sub(1, Ordering::Relaxed);
+    let ptr = ptr.sub(HEADER);
+    alloc::dealloc(ptr, ptr.cast::<Layout>().read());
+}
+
+static ALLOCATOR: CppAllocator = CppAllocator {
+    allocate,
+    deallocate,
+};
+
+#[test]
+fn allocator() {
+    // nothing is boxed in this test binary before the allocator is installed
+    unsafe { vtable_gen::set_allocator(&ALLOCATOR) };
+
+    let rect = CppBox::new(Rect::new(2, 3));
+    assert_eq!(LIVE.load(Ordering::Relaxed), 1);
+
+    // freed as its base, by the allocator that allocated it
+    let shape = unsafe { CppBox::from_raw(CppBox::into_raw(rect).cast::<Shape>()) };
+    assert_eq!(shape.area(), 6);
+    drop(shape);
+    assert_eq!(LIVE.load(Ordering::Relaxed), 0);
+
+    // freed if the construction panics
+    let result = panic::catch_unwind(|| unsafe {
+        CppBox::<Rect>::new_in_place(|_| panic!("construction failed"))
+    });
+    assert!(result.is_err());
+    assert_eq!(LIVE.load(Ordering::Relaxed), 0);
+
+    // an instance with a secondary base is freed from where it starts
+    let label = CppBox::new(Label::new(4, 5));
+    assert_eq!(LIVE.load(Ordering::Relaxed), 1);
+    assert_ne!(
+        ptr::addr_of!(label.base_tag).cast::<u8>(),
+        CppBox::as_ptr(&label).cast::<u8>()
+    );
+    assert_eq!(label.tag(), 5);
+
+    let shape = unsafe { CppBox::from_raw(CppBox::into_raw(label).cast::<Shape>()) };
+    assert_eq!(shape.area(), 4);
+    drop(shape);
+    assert_eq!(LIVE.load(Ordering::Relaxed), 0);
+}
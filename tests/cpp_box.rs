@@ -0,0 +1,178 @@
+#![cfg(feature = "alloc")]
+
+use std::cell::RefCell;
+use std::pin::Pin;
+use std::ptr;
+use std::rc::Rc;
+
+use vtable_gen::{cpp_class, CppBox};
+
+type Log = Rc<RefCell<Vec<&'static str>>>;
+
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Resource {
+        log: Log,
+
+        virtual fn id(&self) -> u32,
+        virtual #[destructor] fn destroy(&mut self)
+    }
+
+    impl Resource {
+        fn new(log: Log) -> Self {
+            Self { log }
+        }
+    }
+}
+
+impl Drop for Resource {
+    fn drop(&mut self) {
+        self.log.borrow_mut().push("resource");
+    }
+}
+
+impl ResourceVirtuals for Resource {
+    extern "C" fn id(_this: &Resource) -> u32 {
+        1
+    }
+
+    unsafe extern "C" fn destroy(this: &mut Resource) {
+        unsafe { ptr::drop_in_place(this) }
+    }
+}
+
+// derived classes destroy instances through the destructor of their base
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct File: Resource {
+        name: String,
+    }
+
+    impl File {
+        fn new(log: Log, name: &str) -> Self {
+            Self {
+                base_resource: Resource::new(log),
+                name: name.to_owned(),
+            }
+        }
+    }
+}
+
+impl Drop for File {
+    fn drop(&mut self) {
+        self.log.borrow_mut().push("file");
+    }
+}
+
+impl ResourceVirtuals for File {
+    extern "C" fn id(_this: &Resource) -> u32 {
+        2
+    }
+
+    unsafe extern "C" fn destroy(this: &mut Resource) {
+        // `this` is borrowed through the whole instance, so it can be converted back
+        unsafe { ptr::drop_in_place(File::from_base_resource_mut(this)) }
+    }
+}
+
+impl FileVirtuals for File {}
+
+// classes without a virtual destructor are dropped
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Plain {
+        log: Log,
+
+        virtual fn id(&self) -> u32
+    }
+
+    impl Plain {
+        fn new(log: Log) -> Self {
+            Self { log }
+        }
+    }
+}
+
+impl Drop for Plain {
+    fn drop(&mut self) {
+        self.log.borrow_mut().push("plain");
+    }
+}
+
+impl PlainVirtuals for Plain {
+    extern "C" fn id(_this: &Plain) -> u32 {
+        3
+    }
+}
+
+cpp_class! {
+    #[pinned]
+    #[gen_vtable(no_unimpl)]
+    struct Anchor {
+        log: Log,
+
+        virtual #[destructor] fn destroy(&mut self)
+    }
+
+    impl Anchor {
+        fn new(log: Log) -> Self {
+            Self { log }
+        }
+    }
+}
+
+impl AnchorVirtuals for Anchor {
+    unsafe extern "C" fn destroy(this: &mut Anchor) {
+        this.log.borrow_mut().push("anchor");
+        unsafe { ptr::drop_in_place(this) }
+    }
+}
+
+fn new_log() -> Log {
+    Rc::new(RefCell::new(Vec::new()))
+}
+
+#[test]
+fn virtual_destructor() {
+    let log = new_log();
+    drop(CppBox::new(File::new(log.clone(), "a.txt")));
+
+    assert_eq!(*log.borrow(), ["file", "resource"]);
+}
+
+#[test]
+fn ownership_transfer() {
+    let log = new_log();
+    let file = CppBox::new(File::new(log.clone(), "a.txt"));
+
+    // C++ owns the instance through its base, and gives it back to be freed as its base
+    let resource = CppBox::into_raw(file).cast::<Resource>();
+    assert!(log.borrow().is_empty());
+    let resource = unsafe { CppBox::from_raw(resource) };
+
+    assert_eq!(resource.id(), 2);
+    drop(resource);
+    assert_eq!(*log.borrow(), ["file", "resource"]);
+}
+
+#[test]
+fn without_destructor() {
+    let log = new_log();
+    let plain = CppBox::new(Plain::new(log.clone()));
+
+    assert_eq!(plain.id(), 3);
+    drop(plain);
+    assert_eq!(*log.borrow(), ["plain"]);
+}
+
+#[test]
+fn in_place() {
+    let log = new_log();
+    let mut anchor = unsafe { CppBox::new_in_place(|ptr| Anchor::construct_at(ptr, log.clone())) };
+
+    let ptr = CppBox::as_ptr(&anchor);
+    let pinned: Pin<&mut Anchor> = CppBox::as_pin_mut(&mut anchor);
+    assert!(ptr::eq(&*pinned, ptr));
+    drop(anchor);
+    assert_eq!(*log.borrow(), ["anchor"]);
+}
@@ -2,15 +2,40 @@
 //! Copyright (C) Warsaw Revamped. Any unauthorized use, modification, or distribution of any portion of this file is prohibited. All rights reserved.
 //!
 
-use vtable_gen::cpp_class;
+use vtable_gen::{cpp_class, CppClass};
 
-use crate::cross::A;
-use crate::cross::AVTable;
+use crate::cross::{A, AVirtuals};
 
 mod cross;
 
 cpp_class! {
+    #[gen_vtable(no_unimpl)]
     pub struct B: A {
         b: u32
     }
+
+    impl B {
+        fn new(b: u32) -> Self {
+            Self {
+                base_a: A::default(),
+                b
+            }
+        }
+    }
+}
+
+impl AVirtuals for B {
+    extern "C" fn a(this: &A) -> u32 {
+        this.a + 1
+    }
+}
+
+impl BVirtuals for B {}
+
+#[test]
+fn imported_base() {
+    let b = B::new(2);
+
+    assert_eq!((B::vtable().base_a.a)(&b), 1);
+    assert_eq!(b.b, 2);
 }
@@ -0,0 +1,96 @@
+use std::pin::Pin;
+
+use vtable_gen::cpp_class;
+
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Socket {
+        port: u16,
+
+        virtual fn port(&self) -> u16
+    }
+
+    impl Socket {
+        fn open(port: u16) -> Result<Self, String> {
+            if port == 0 {
+                return Err("port 0 is reserved".to_string());
+            }
+            Ok(Self { port })
+        }
+
+        fn any(port: Option<u16>) -> Option<Self> {
+            port.map(|port| Self { port })
+        }
+
+        fn boxed(port: u16) -> Box<Self> {
+            Box::new(Self { port })
+        }
+
+        fn pinned(port: u16) -> Pin<Box<Self>> {
+            Box::pin(Self { port })
+        }
+
+        fn fallback() -> Self {
+            Self::open(80).unwrap()
+        }
+    }
+}
+
+impl SocketVirtuals for Socket {
+    extern "C" fn port(this: &Socket) -> u16 {
+        this.port
+    }
+}
+
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Tls: Socket {
+        virtual fn secure(&self) -> bool
+    }
+
+    impl Tls {
+        fn open(port: u16) -> Result<Self, String> {
+            Ok(Self {
+                base_socket: Socket::open(port)?,
+            })
+        }
+
+        fn boxed(port: u16) -> Box<Self> {
+            Box::new(Self {
+                base_socket: *Socket::boxed(port),
+            })
+        }
+    }
+}
+
+impl SocketVirtuals for Tls {
+    extern "C" fn port(this: &Socket) -> u16 {
+        this.port + 1
+    }
+}
+
+impl TlsVirtuals for Tls {
+    extern "C" fn secure(_this: &Tls) -> bool {
+        true
+    }
+}
+
+#[test]
+fn wrapped() {
+    assert_eq!(Socket::open(8).unwrap().port(), 8);
+    assert!(Socket::open(0).is_err());
+    assert_eq!(Socket::any(Some(9)).unwrap().port(), 9);
+    assert!(Socket::any(None).is_none());
+    assert_eq!(Socket::boxed(10).port(), 10);
+    assert_eq!(Socket::pinned(11).port(), 11);
+    assert_eq!(Socket::fallback().port(), 80);
+}
+
+#[test]
+fn derived() {
+    let tls = Tls::open(8).unwrap();
+    assert_eq!(tls.port(), 9);
+    assert!(tls.secure());
+    assert!(Tls::open(0).is_err());
+    assert_eq!(Tls::boxed(10).port(), 11);
+}
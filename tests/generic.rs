@@ -1,3 +1,5 @@
+#![cfg(feature = "alloc")]
+
 use vtable_gen::cpp_class;
 
 cpp_class! {
@@ -6,7 +8,7 @@ cpp_class! {
     struct Foo<const N: u32, T: Default> {
         a: T,
 
-        virtual(1) extern "fastcall" fn func(&self, a: u32, b: f32) -> usize,
+        virtual(1) extern "system" fn func(&self, a: u32, b: f32) -> usize,
     }
 
     impl<const N: u32, T: Default> Foo<N, T> {
@@ -15,10 +17,16 @@ cpp_class! {
             Self { a }
         }
     }
+
+    impl<const N: u32, T: Clone + Default> Clone for Foo<N, T> {
+        fn clone(&self) -> Self {
+            Self { a: self.a.clone() }
+        }
+    }
 }
 
 impl<const N: u32, T: Default> FooVirtuals<N, T> for Foo<N, T> {
-    extern "fastcall" fn func(_this: &Foo<N, T>, a: u32, b: f32) -> usize {
+    extern "system" fn func(_this: &Foo<N, T>, a: u32, b: f32) -> usize {
         N as usize + a as usize + b as usize
     }
 }
@@ -51,7 +59,7 @@ fn unimpl_method() {
     let b = Foo::<2, u32>::new(2);
 
     // ensure that unimplemented methods panic
-    (unsafe { &*(b.vfptr as *const FooVTable<2, u32>) }.unimpl_0)()
+    (b.vfptr.unimpl_0)()
 }
 
 #[test]
@@ -63,3 +71,13 @@ fn default() {
     // call through the vtable
     assert_eq!(b.func(1, 2.0), 5);
 }
+
+#[test]
+fn trait_impl() {
+    let b = Foo::<2, u32>::new(2);
+    let c = b.clone();
+
+    assert_eq!(c.a, 2);
+    assert_eq!(c.vfptr.as_ptr(), b.vfptr.as_ptr());
+    assert_eq!(c.func(1, 2.0), 5);
+}
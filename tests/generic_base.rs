@@ -14,7 +14,6 @@ impl FooImplVirtuals for FooImpl {
     }
 }
 
-// currently, we don't support impls. you can do them manually if needed
 cpp_class! {
     #[impl_generic_base([T = FooImpl])]
     #[derive(Debug, Default)]
@@ -22,7 +21,7 @@ cpp_class! {
     struct Foo<T>: T {
         a: u32
 
-        virtual(1) extern "fastcall" fn foo2(&self, a: u32, b: f32) -> usize,
+        virtual(1) extern "system" fn foo2(&self, a: u32, b: f32) -> usize,
     }
 }
 
@@ -33,7 +32,7 @@ impl FooImplVirtuals for Foo_FooImpl {
 }
 
 impl Foo_FooImplVirtuals for Foo_FooImpl {
-    extern "fastcall" fn foo2(this: &Foo_FooImpl, a: u32, b: f32) -> usize {
+    extern "system" fn foo2(this: &Foo_FooImpl, a: u32, b: f32) -> usize {
         this.base_foo_impl.foo() as usize + a as usize + b as usize
     }
 }
@@ -56,7 +55,7 @@ fn unimpl_method() {
     let b = Foo_FooImpl::default();
 
     // ensure that unimplemented methods panic
-    (unsafe { &*(b.base_foo_impl.vfptr as *const FooImplVTable) }.unimpl_0)()
+    (b.base_foo_impl.vfptr.unimpl_0)()
 }
 
 #[test]
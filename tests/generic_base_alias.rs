@@ -0,0 +1,163 @@
+#![cfg(feature = "alloc")]
+
+use vtable_gen::cpp_class;
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct Engine {
+        virtual fn power(&self) -> u32
+    }
+}
+
+impl EngineVirtuals for Engine {
+    extern "C" fn power(_this: &Engine) -> u32 {
+        100
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct Motor {
+        virtual fn power(&self) -> u32
+    }
+}
+
+impl MotorVirtuals for Motor {
+    extern "C" fn power(_this: &Motor) -> u32 {
+        50
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    #[impl_generic_base([T = Engine], [T = Motor])]
+    struct Car<T>: T {
+        wheels: u32,
+
+        virtual fn wheels(&self) -> u32
+    }
+}
+
+// the instantiations are implemented through the aliases
+impl EngineVirtuals for Car<Engine> {
+    extern "C" fn power(_this: &Engine) -> u32 {
+        200
+    }
+}
+
+impl CarVirtuals<Engine> for Car<Engine> {
+    fn wheels(this: &Car<Engine>) -> u32 {
+        this.wheels
+    }
+}
+
+impl MotorVirtuals for Car<Motor> {
+    extern "C" fn power(_this: &Motor) -> u32 {
+        75
+    }
+}
+
+impl CarVirtuals<Motor> for Car<Motor> {
+    fn wheels(this: &Car<Motor>) -> u32 {
+        this.wheels + 1
+    }
+}
+
+// the instantiations are named by the template
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    #[generic_base_name = "{base}Truck"]
+    #[impl_generic_base([T = Engine])]
+    struct Truck<T, const N: usize>: T {
+        virtual fn axles(&self) -> usize
+    }
+}
+
+impl<const N: usize> EngineVirtuals for EngineTruck<N> {
+    extern "C" fn power(_this: &Engine) -> u32 {
+        300
+    }
+}
+
+impl<const N: usize> EngineTruckVirtuals<N> for EngineTruck<N> {
+    extern "C" fn axles(_this: &EngineTruck<N>) -> usize {
+        N
+    }
+}
+
+// bases are instantiated by their own template
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    #[impl_generic_base([T = Engine])]
+    struct Trailer<T, const N: usize>: Truck<T, N> {
+        virtual fn load(&self) -> u32
+    }
+}
+
+impl<const N: usize> EngineVirtuals for Trailer_Engine<N> {
+    extern "C" fn power(_this: &Engine) -> u32 {
+        400
+    }
+}
+
+impl<const N: usize> TruckVirtuals<Engine, N> for Trailer_Engine<N> {
+    fn axles(_this: &Truck<Engine, N>) -> usize {
+        N + 2
+    }
+}
+
+impl<const N: usize> TrailerVirtuals<Engine, N> for Trailer_Engine<N> {
+    fn load(_this: &Trailer<Engine, N>) -> u32 {
+        1000
+    }
+}
+
+#[test]
+fn alias() {
+    let car = Car::<Engine>::default();
+    let motor_car: Car<Motor> = Default::default();
+
+    assert_eq!(car.power(), 200);
+    assert_eq!(car.wheels(), 0);
+    assert_eq!(motor_car.power(), 75);
+    assert_eq!(motor_car.wheels(), 1);
+}
+
+#[test]
+fn vtable_alias() {
+    assert_eq!(
+        std::mem::size_of::<CarVTable<Engine>>(),
+        std::mem::size_of::<Car_EngineVTable>()
+    );
+    assert!(std::ptr::eq(
+        Car::<Engine>::default().base_engine.vfptr.as_ptr(),
+        &<Car<Engine> as vtable_gen::CppClass>::vtable().base_engine,
+    ));
+}
+
+#[test]
+fn name_template() {
+    let truck = Truck::<Engine, 3>::default();
+
+    assert_eq!(truck.power(), 300);
+    assert_eq!(truck.axles(), 3);
+    assert_eq!(
+        std::mem::size_of::<TruckVTable<Engine, 3>>(),
+        std::mem::size_of::<EngineTruckVTable<3>>()
+    );
+}
+
+#[test]
+fn base_name_template() {
+    let trailer = Trailer::<Engine, 2>::default();
+    let truck: &EngineTruck<2> = &trailer.base_engine_truck;
+
+    assert_eq!(truck.power(), 400);
+    assert_eq!(trailer.axles(), 4);
+    assert_eq!(trailer.load(), 1000);
+}
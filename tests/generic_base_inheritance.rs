@@ -14,15 +14,23 @@ impl FooImplVirtuals for FooImpl {
     }
 }
 
-// currently, we don't support impls. you can do them manually if needed
 cpp_class! {
     #[derive(Debug, Default)]
-    #[gen_vtable(no_unimpl)]
+    #[gen_vtable(no_unimpl, abi = "system")]
     #[impl_generic_base([T = FooImpl])]
     struct Foo<T>: T {
         a: u32
 
-        virtual(1) extern "fastcall" fn foo2(&self, a: u32, b: f32) -> usize,
+        virtual(1) fn foo2(&self, a: u32, b: f32) -> usize,
+    }
+
+    impl<T: Default> Foo<T> {
+        fn new(a: u32) -> Self {
+            Self {
+                base_t: T::default(),
+                a,
+            }
+        }
     }
 }
 
@@ -33,7 +41,7 @@ impl FooImplVirtuals for Foo_FooImpl {
 }
 
 impl Foo_FooImplVirtuals for Foo_FooImpl {
-    extern "fastcall" fn foo2(this: &Foo_FooImpl, a: u32, b: f32) -> usize {
+    extern "system" fn foo2(this: &Foo_FooImpl, a: u32, b: f32) -> usize {
         this.base_foo_impl.foo() as usize + a as usize + b as usize
     }
 }
@@ -42,7 +50,15 @@ cpp_class! {
     #[gen_vtable(no_unimpl)]
     #[impl_generic_base([T = FooImpl])]
     struct Bar<T>: Foo<T> {
-        virtual extern "fastcall" fn bar(&self) -> u32,
+        virtual fn bar(&self) -> u32,
+    }
+
+    impl<T: Default> Bar<T> {
+        fn new(a: u32) -> Self {
+            Self {
+                base_foo: Foo::<T>::new(a),
+            }
+        }
     }
 }
 
@@ -53,13 +69,13 @@ impl FooImplVirtuals for Bar_FooImpl {
 }
 
 impl Foo_FooImplVirtuals for Bar_FooImpl {
-    extern "fastcall" fn foo2(this: &Foo_FooImpl, a: u32, b: f32) -> usize {
+    extern "system" fn foo2(this: &Foo_FooImpl, a: u32, b: f32) -> usize {
         this.a as usize + a as usize - b as usize
     }
 }
 
 impl Bar_FooImplVirtuals for Bar_FooImpl {
-    extern "fastcall" fn bar(_this: &Bar_FooImpl) -> u32 {
+    extern "system" fn bar(_this: &Bar_FooImpl) -> u32 {
         19
     }
 }
@@ -82,7 +98,7 @@ fn unimpl_method() {
     let b = Foo_FooImpl::default();
 
     // ensure that unimplemented methods panic
-    (unsafe { &*(b.base_foo_impl.vfptr as *const FooImplVTable) }.unimpl_0)()
+    (b.base_foo_impl.vfptr.unimpl_0)()
 }
 
 #[test]
@@ -96,3 +112,15 @@ fn default() {
     assert_eq!(b.foo(), 13);
     assert_eq!(b.foo2(1, 2.0), 16);
 }
+
+#[test]
+fn constructors() {
+    let foo = Foo_FooImpl::new(4);
+    let bar = Bar_FooImpl::new(5);
+
+    assert_eq!(foo.foo(), 13);
+    assert_eq!(foo.foo2(1, 2.0), 16);
+    assert_eq!(bar.foo(), 17);
+    assert_eq!(bar.foo2(1, 2.0), 4);
+    assert_eq!(bar.bar(), 19);
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "alloc")]
+
 use vtable_gen::cpp_class;
 
 cpp_class! {
@@ -106,6 +108,7 @@ fn basic() {
     // call through the vtables
     assert_eq!(c.a(), 2);
     assert_eq!(<C<11> as AsRef<B<u32, 11>>>::as_ref(&c).b(), 3);
+    assert_eq!(c.b(), 3);
     assert_eq!(c.c(), 6);
 }
 
@@ -121,5 +124,16 @@ fn default() {
     // call through the vtables
     assert_eq!(c.a(), 1);
     assert_eq!(<C<11> as AsRef<B<u32, 11>>>::as_ref(&c).b(), 1);
+    assert_eq!(c.b(), 1);
     assert_eq!(c.c(), 0);
 }
+
+#[test]
+fn from_base() {
+    let c = C::<11>::new(1, 2, 3);
+
+    // convert each base back into the class
+    let b: &B<u32, 11> = c.as_ref();
+    assert!(std::ptr::eq(unsafe { C::from_base_b(b) }, &c));
+    assert_eq!(unsafe { C::<11>::from_base_b(b) }.c, 3);
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "alloc")]
+
 use vtable_gen::cpp_class;
 
 cpp_class! {
@@ -161,6 +163,7 @@ fn basic() {
     // call through the vtables
     assert_eq!(c.a(), 3);
     assert_eq!(<C<23, u32> as AsRef<B<u32, 23>>>::as_ref(&c).b(), 4);
+    assert_eq!(c.b(), 4);
     assert_eq!(c.c(), 27);
     assert_eq!(c.d(), 4);
 }
@@ -1,3 +1,5 @@
+#![cfg(feature = "alloc")]
+
 use vtable_gen::cpp_class;
 
 cpp_class! {
@@ -6,7 +8,7 @@ cpp_class! {
     struct Foo<T: Default> {
         a: T,
 
-        virtual(1) extern "fastcall" fn func(&self, a: u32, b: f32) -> usize,
+        virtual(1) extern "system" fn func(&self, a: u32, b: f32) -> usize,
     }
 
     impl<T: Default> Foo<T> {
@@ -18,7 +20,7 @@ cpp_class! {
 }
 
 impl<T: Default> FooVirtuals<T> for Foo<T> {
-    extern "fastcall" fn func(_this: &Foo<T>, a: u32, b: f32) -> usize {
+    extern "system" fn func(_this: &Foo<T>, a: u32, b: f32) -> usize {
         a as usize + b as usize
     }
 }
@@ -43,7 +45,7 @@ cpp_class! {
 }
 
 impl<const N: u32, T: Default> FooVirtuals<T> for Bar<N, T> {
-    extern "fastcall" fn func(_this: &Foo<T>, a: u32, b: f32) -> usize {
+    extern "system" fn func(_this: &Foo<T>, a: u32, b: f32) -> usize {
         a as usize + b as usize + N as usize
     }
 }
@@ -85,7 +87,7 @@ fn unimpl_method() {
     let b = Bar::<23, u32>::new(2, 3);
 
     // ensure that unimplemented methods panic
-    (unsafe { &*(b.vfptr as *const FooVTable<u32>) }.unimpl_0)()
+    (b.vfptr.unimpl_0)()
 }
 
 #[test]
@@ -1,3 +1,5 @@
+#![cfg(feature = "alloc")]
+
 use vtable_gen::cpp_class;
 
 cpp_class! {
@@ -6,7 +8,7 @@ cpp_class! {
     struct Foo<const N: u32, T: Default> {
         a: T,
 
-        virtual(1) extern "fastcall" fn func(&self, a: u32, b: f32) -> usize,
+        virtual(1) extern "system" fn func(&self, a: u32, b: f32) -> usize,
     }
 
     impl<const N: u32, T: Default> Foo<N, T> {
@@ -18,7 +20,7 @@ cpp_class! {
 }
 
 impl<const N: u32, T: Default> FooVirtuals<N, T> for Foo<N, T> {
-    extern "fastcall" fn func(_this: &Foo<N, T>, a: u32, b: f32) -> usize {
+    extern "system" fn func(_this: &Foo<N, T>, a: u32, b: f32) -> usize {
         a as usize + b as usize
     }
 }
@@ -43,7 +45,7 @@ cpp_class! {
 }
 
 impl<U: Default, const O: u32> FooVirtuals<O, U> for Bar<U, O> {
-    extern "fastcall" fn func(_this: &Foo<O, U>, a: u32, b: f32) -> usize {
+    extern "system" fn func(_this: &Foo<O, U>, a: u32, b: f32) -> usize {
         a as usize + b as usize + O as usize
     }
 }
@@ -74,7 +76,7 @@ cpp_class! {
 }
 
 impl<T: Default> FooVirtuals<19, T> for Baz<T> {
-    extern "fastcall" fn func(_this: &Foo<19, T>, a: u32, b: f32) -> usize {
+    extern "system" fn func(_this: &Foo<19, T>, a: u32, b: f32) -> usize {
         a as usize + b as usize + 2
     }
 }
@@ -126,7 +128,7 @@ fn unimpl_method() {
     let b = Baz::<u32>::new(1, 2, 3);
 
     // ensure that unimplemented methods panic
-    (unsafe { &*(b.vfptr as *const FooVTable<19, u32>) }.unimpl_0)()
+    (b.vfptr.unimpl_0)()
 }
 
 #[test]
@@ -0,0 +1,153 @@
+use vtable_gen::cpp_class;
+
+struct Allocator {
+    base: u32,
+}
+
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Arena<'a> {
+        alloc: &'a Allocator,
+
+        virtual fn allocate(&self, size: u32) -> u32,
+        virtual fn allocator(&self) -> &'a Allocator
+    }
+
+    impl<'a> Arena<'a> {
+        fn new(alloc: &'a Allocator) -> Self {
+            Self { alloc }
+        }
+    }
+}
+
+impl ArenaVirtuals for Arena<'_> {
+    extern "C" fn allocate(this: &Arena, size: u32) -> u32 {
+        this.alloc.base + size
+    }
+
+    extern "C" fn allocator<'a>(this: &Arena<'a>) -> &'a Allocator {
+        this.alloc
+    }
+}
+
+// the VTables of generic classes are registered, which needs `alloc`
+#[cfg(feature = "alloc")]
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Pool<'a, T: Default>: Arena<'a> {
+        item: T,
+
+        virtual fn item(&self) -> &T
+    }
+
+    impl<'a, T: Default> Pool<'a, T> {
+        fn new(alloc: &'a Allocator) -> Self {
+            Self {
+                base_arena: Arena::new(alloc),
+                item: T::default(),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Default> ArenaVirtuals for Pool<'_, T> {
+    extern "C" fn allocate(this: &Arena, size: u32) -> u32 {
+        this.alloc.base + size * 2
+    }
+
+    extern "C" fn allocator<'a>(this: &Arena<'a>) -> &'a Allocator {
+        this.alloc
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Default> PoolVirtuals<T> for Pool<'_, T> {
+    extern "C" fn item<'b>(this: &'b Pool<'_, T>) -> &'b T {
+        &this.item
+    }
+}
+
+// panic policies need `std`
+#[cfg(feature = "std")]
+cpp_class! {
+    #[gen_vtable(no_unimpl, on_panic = "default")]
+    struct Cursor<'a> {
+        data: &'a [u8],
+
+        virtual fn peek(&self, idx: usize) -> Option<&'a u8>
+    }
+
+    impl<'a> Cursor<'a> {
+        fn new(data: &'a [u8]) -> Self {
+            Self { data }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl CursorVirtuals for Cursor<'_> {
+    fn peek<'a>(this: &Cursor<'a>, idx: usize) -> Option<&'a u8> {
+        Some(&this.data[idx])
+    }
+}
+
+// only looking up the VTable needs the type parameters to be 'static
+#[cfg(feature = "alloc")]
+fn item_of<'b, T: Default>(pool: &'b Pool<'_, T>) -> &'b T {
+    pool.item()
+}
+
+// only checks that the unimplemented virtuals are generated
+cpp_class! {
+    #[gen_vtable]
+    struct Reader<'a> {
+        data: &'a [u8],
+
+        virtual fn read(&mut self) -> &u8
+    }
+}
+
+#[test]
+fn borrowed() {
+    let alloc = Allocator { base: 10 };
+    let arena = Arena::new(&alloc);
+
+    assert_eq!(arena.allocate(1), 11);
+    assert!(std::ptr::eq(arena.allocator(), &alloc));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn derived() {
+    let alloc = Allocator { base: 20 };
+    let pool = Pool::<u32>::new(&alloc);
+
+    assert_eq!(pool.allocate(1), 22);
+    assert!(std::ptr::eq(pool.allocator(), &alloc));
+    assert_eq!(*pool.item(), 0);
+    assert_eq!(*item_of(&pool), 0);
+}
+
+#[test]
+fn shared_vtable() {
+    let first = Allocator { base: 1 };
+    let second = Allocator { base: 2 };
+
+    // the VTable doesn't depend on the lifetime of the instance
+    assert!(std::ptr::eq(
+        Arena::new(&first).vfptr.as_ptr(),
+        Arena::new(&second).vfptr.as_ptr()
+    ));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn guarded() {
+    let data = [1, 2, 3];
+    let cursor = Cursor::new(&data);
+
+    assert_eq!(cursor.peek(1), Some(&2));
+    // the panic is turned into the default value
+    assert_eq!(cursor.peek(3), None);
+}
@@ -0,0 +1,136 @@
+use std::fmt;
+
+use vtable_gen::cpp_class;
+
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Shape {
+        sides: u32,
+
+        virtual fn area(&self) -> f32
+    }
+
+    impl Shape {
+        fn new(sides: u32) -> Self {
+            Self { sides }
+        }
+
+        fn triangle() -> Self {
+            Self::new(3)
+        }
+
+        // methods and associated functions that don't instantiate are passed through
+        fn sides(&self) -> u32 {
+            self.sides
+        }
+
+        fn max_sides() -> u32 {
+            12
+        }
+    }
+
+    impl Shape {
+        // methods that instantiate use the vtables of the class
+        fn with_sides(&self, sides: u32) -> Self {
+            Self { sides: sides.min(Self::max_sides()) }
+        }
+
+        // unless they're marked as constructors
+        #[ctor]
+        fn doubled(&self) -> Self {
+            Self { sides: self.sides * 2 }
+        }
+    }
+
+    impl fmt::Display for Shape {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}-gon", self.sides)
+        }
+    }
+
+    impl From<u32> for Shape {
+        fn from(sides: u32) -> Self {
+            Self::new(sides)
+        }
+    }
+}
+
+impl ShapeVirtuals for Shape {
+    extern "C" fn area(this: &Shape) -> f32 {
+        this.sides as f32
+    }
+}
+
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Square: Shape {
+        len: f32,
+
+        virtual fn len(&self) -> f32
+    }
+
+    impl Square {
+        fn new(len: f32) -> Self {
+            Self {
+                base_shape: Shape::new(4),
+                len,
+            }
+        }
+
+        fn from_shape(shape: &Shape, len: f32) -> Self {
+            Self {
+                base_shape: shape.doubled(),
+                len,
+            }
+        }
+
+        #[ctor]
+        fn scaled(&self, factor: f32) -> Self {
+            Self::new(self.len * factor)
+        }
+    }
+}
+
+impl ShapeVirtuals for Square {
+    extern "C" fn area(this: &Shape) -> f32 {
+        let this = unsafe { Square::from_base_shape(this) };
+        this.len * this.len
+    }
+}
+
+impl SquareVirtuals for Square {
+    extern "C" fn len(this: &Square) -> f32 {
+        this.len
+    }
+}
+
+#[test]
+fn passed_through() {
+    let shape = Shape::triangle();
+
+    assert_eq!(shape.sides(), 3);
+    assert_eq!(Shape::max_sides(), 12);
+    assert_eq!(shape.to_string(), "3-gon");
+}
+
+#[test]
+fn instantiating_methods() {
+    let shape = Shape::from(5);
+
+    assert_eq!(shape.area(), 5.0);
+    assert_eq!(shape.with_sides(20).area(), 12.0);
+    assert_eq!(shape.doubled().area(), 10.0);
+}
+
+#[test]
+fn derived_constructors() {
+    let square = Square::new(2.0);
+    let scaled = square.scaled(2.0);
+    let from_shape = Square::from_shape(&Shape::new(2), 3.0);
+
+    assert_eq!(square.area(), 4.0);
+    assert_eq!(scaled.area(), 16.0);
+    assert_eq!(scaled.len(), 4.0);
+    assert_eq!(from_shape.sides(), 4);
+    assert_eq!(from_shape.area(), 9.0);
+}
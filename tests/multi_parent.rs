@@ -103,6 +103,7 @@ fn basic() {
     // call through the vtables
     assert_eq!(c.a(), 2);
     assert_eq!(<C as AsRef<B>>::as_ref(&c).b(), 3);
+    assert_eq!(c.b(), 3);
     assert_eq!(c.c(), 6);
 }
 
@@ -118,5 +119,19 @@ fn default() {
     // call through the vtables
     assert_eq!(c.a(), 1);
     assert_eq!(<C as AsRef<B>>::as_ref(&c).b(), 1);
+    assert_eq!(c.b(), 1);
     assert_eq!(c.c(), 0);
 }
+
+#[test]
+fn from_base() {
+    let mut c = C::new(1, 2, 3);
+
+    // convert each base back into the class
+    let a: &A = c.as_ref();
+    assert!(std::ptr::eq(unsafe { C::from_base_a(a) }, &c));
+    let b: &B = c.as_ref();
+    assert!(std::ptr::eq(unsafe { C::from_base_b(b) }, &c));
+    let b: &mut B = c.as_mut();
+    assert_eq!(unsafe { C::from_base_b_mut(b) }.c, 3);
+}
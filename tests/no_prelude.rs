@@ -0,0 +1,102 @@
+// generated code must resolve without the standard prelude, as in `#![no_std]` crates
+mod firmware {
+    #![no_implicit_prelude]
+
+    ::vtable_gen::cpp_class! {
+        #[derive(Default)]
+        #[gen_vtable]
+        pub struct A {
+            pub a: u32,
+
+            virtual pub fn a(&self) -> u32
+        }
+
+        impl A {
+            pub fn new(a: u32) -> Self {
+                Self { a }
+            }
+        }
+    }
+
+    ::vtable_gen::cpp_class! {
+        #[derive(Default)]
+        #[gen_vtable]
+        pub struct B {
+            virtual pub fn b(&self) -> u32
+        }
+    }
+
+    ::vtable_gen::cpp_class! {
+        #[derive(Default)]
+        #[gen_vtable(no_unimpl)]
+        pub struct C: A, B {
+            pub c: u32,
+
+            virtual pub fn c(&self) -> u32
+        }
+
+        impl C {
+            pub fn new(a: u32, c: u32) -> Self {
+                Self {
+                    base_a: A::new(a),
+                    base_b: B::default(),
+                    c,
+                }
+            }
+        }
+    }
+
+    impl AVirtuals for C {
+        extern "C" fn a(this: &A) -> u32 {
+            this.a
+        }
+    }
+
+    impl BVirtuals for C {
+        extern "C" fn b(_this: &B) -> u32 {
+            2
+        }
+    }
+
+    impl CVirtuals for C {
+        extern "C" fn c(this: &C) -> u32 {
+            this.c
+        }
+    }
+
+    // the VTables of generic classes are registered, which needs `alloc`
+    #[cfg(feature = "alloc")]
+    ::vtable_gen::cpp_class! {
+        #[derive(Default)]
+        #[gen_vtable(no_unimpl)]
+        pub struct G<T: ::core::default::Default> {
+            pub t: T,
+
+            virtual pub fn g(&self) -> u32
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<T: ::core::default::Default> GVirtuals<T> for G<T> {
+        extern "C" fn g(_this: &G<T>) -> u32 {
+            4
+        }
+    }
+}
+
+use firmware::{B, C};
+
+#[test]
+fn no_prelude() {
+    let c = C::new(1, 3);
+
+    assert_eq!(c.a(), 1);
+    assert_eq!(<C as AsRef<B>>::as_ref(&c).b(), 2);
+    assert_eq!(c.c(), 3);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn no_prelude_generic() {
+    assert_eq!(firmware::G::<u8>::default().g(), 4);
+}
@@ -0,0 +1,148 @@
+#![cfg(feature = "std")]
+
+use std::env;
+use std::panic;
+use std::process::Command;
+
+use vtable_gen::cpp_class;
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl, on_panic = "default")]
+    struct A {
+        virtual fn a(&self) -> u32,
+        virtual fn b(&mut self, value: u32)
+    }
+}
+
+impl AVirtuals for A {
+    fn a(_this: &A) -> u32 {
+        panic!("a")
+    }
+
+    fn b(_this: &mut A, _value: u32) {
+        panic!("b")
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl, on_panic = "log_and_default")]
+    struct B: A {
+        virtual fn c(&self) -> u64
+    }
+}
+
+impl AVirtuals for B {
+    fn a(_this: &A) -> u32 {
+        panic!("a")
+    }
+
+    fn b(_this: &mut A, _value: u32) {}
+}
+
+impl BVirtuals for B {
+    fn c(_this: &B) -> u64 {
+        panic!("c")
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl, on_panic = "default")]
+    struct G<T: Default> {
+        g: T,
+
+        virtual fn g(&self) -> u32
+    }
+}
+
+impl<T: Default> GVirtuals<T> for G<T> {
+    fn g(_this: &G<T>) -> u32 {
+        panic!("g")
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl, on_panic = "abort")]
+    struct R {
+        r: u32,
+
+        virtual fn r(&self) -> &u32,
+        virtual fn p(&self) -> u32
+    }
+}
+
+impl RVirtuals for R {
+    fn r(this: &R) -> &u32 {
+        &this.r
+    }
+
+    fn p(_this: &R) -> u32 {
+        panic!("p")
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl, on_panic = "unwind")]
+    struct U {
+        virtual fn u(&self) -> u32
+    }
+}
+
+impl UVirtuals for U {
+    extern "C-unwind" fn u(_this: &U) -> u32 {
+        panic!("u")
+    }
+}
+
+#[test]
+fn default() {
+    let mut a = A::default();
+    assert_eq!(a.a(), 0);
+    a.b(1);
+
+    let g = G::<u32>::default();
+    assert_eq!(g.g(), 0);
+}
+
+#[test]
+fn inherited() {
+    let mut b = B::default();
+    assert_eq!(b.a(), 0);
+    b.b(1);
+    assert_eq!(b.c(), 0);
+}
+
+#[test]
+fn abort() {
+    let r = R::default();
+    assert_eq!(*r.r(), 0);
+
+    // the panic aborts the process, so it's raised in a child process running this test
+    if env::var_os("VTABLE_GEN_ABORT").is_some() {
+        r.p();
+        unreachable!("the panic didn't abort");
+    }
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["abort", "--exact", "--nocapture"])
+        .env("VTABLE_GEN_ABORT", "1")
+        .output()
+        .unwrap();
+    #[cfg(unix)]
+    assert_eq!(
+        std::os::unix::process::ExitStatusExt::signal(&output.status),
+        Some(6)
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("panic in virtual R::p: p"), "{stderr}");
+}
+
+#[test]
+fn unwind() {
+    let u = U::default();
+    assert!(panic::catch_unwind(|| u.u()).is_err());
+}
@@ -0,0 +1,210 @@
+#![cfg(feature = "alloc")]
+
+use std::pin::Pin;
+use std::ptr;
+
+use vtable_gen::cpp_class;
+
+// instances register their own address, so they must not move after construction
+cpp_class! {
+    #[pinned]
+    #[gen_vtable(no_unimpl)]
+    struct Node {
+        value: u32,
+        this: *const Node,
+
+        virtual fn value(&self) -> u32,
+        virtual fn set_value(&mut self, value: u32)
+    }
+
+    impl Node {
+        fn new(value: u32) -> Self {
+            Self {
+                value,
+                this: ptr::null(),
+            }
+        }
+
+        fn register(self: Pin<&mut Self>) {
+            let this = unsafe { self.get_unchecked_mut() };
+            this.this = this;
+        }
+
+        fn is_registered(&self) -> bool {
+            ptr::eq(self.this, self)
+        }
+    }
+}
+
+impl NodeVirtuals for Node {
+    extern "C" fn value(this: &Node) -> u32 {
+        this.value
+    }
+
+    extern "C" fn set_value(this: &mut Node, value: u32) {
+        this.value = value;
+    }
+}
+
+// pinning is inherited
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Leaf: Node {
+        depth: u32,
+
+        virtual fn depth(&self) -> u32
+    }
+
+    impl Leaf {
+        fn new(value: u32, depth: u32) -> Self {
+            Self {
+                base_node: Node::new(value),
+                depth,
+            }
+        }
+    }
+}
+
+impl NodeVirtuals for Leaf {
+    extern "C" fn value(this: &Node) -> u32 {
+        this.value * 10
+    }
+
+    extern "C" fn set_value(this: &mut Node, value: u32) {
+        this.value = value + 1;
+    }
+}
+
+impl LeafVirtuals for Leaf {
+    extern "C" fn depth(this: &Leaf) -> u32 {
+        this.depth
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct Named {
+        virtual fn id(&self) -> u32
+    }
+}
+
+impl NamedVirtuals for Named {
+    extern "C" fn id(_this: &Named) -> u32 {
+        1
+    }
+}
+
+// mutable virtuals of pinned secondary bases are forwarded through pinned references
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct NamedNode: Named, Node {}
+
+    impl NamedNode {
+        fn new(value: u32) -> Self {
+            Self {
+                base_named: Named::default(),
+                base_node: Node::new(value),
+            }
+        }
+    }
+}
+
+impl NamedVirtuals for NamedNode {
+    extern "C" fn id(_this: &Named) -> u32 {
+        2
+    }
+}
+
+impl NodeVirtuals for NamedNode {
+    extern "C" fn value(this: &Node) -> u32 {
+        this.value
+    }
+
+    extern "C" fn set_value(this: &mut Node, value: u32) {
+        this.value = value * 2;
+    }
+}
+
+impl NamedNodeVirtuals for NamedNode {}
+
+// the arguments are free to share names with the slot
+cpp_class! {
+    #[pinned]
+    #[gen_vtable(no_unimpl)]
+    struct Cell {
+        slot: u32,
+
+        virtual fn slot(&self) -> u32
+    }
+
+    impl Cell {
+        fn new(slot: u32) -> Self { Self { slot } }
+    }
+}
+
+impl CellVirtuals for Cell {
+    extern "C" fn slot(this: &Cell) -> u32 {
+        this.slot
+    }
+}
+
+#[test]
+fn boxed() {
+    let mut node = Node::new(3);
+    node.as_mut().register();
+
+    assert!(node.is_registered());
+    assert_eq!(node.value(), 3);
+
+    node.as_mut().set_value(4);
+    assert_eq!(node.value(), 4);
+    assert!(node.is_registered());
+}
+
+#[test]
+fn in_place() {
+    let mut slot = std::mem::MaybeUninit::<Node>::uninit();
+    let node = unsafe {
+        Node::new_in_place(slot.as_mut_ptr(), 5);
+        Pin::new_unchecked(slot.assume_init_mut())
+    };
+    node.register();
+
+    let node = unsafe { slot.assume_init_ref() };
+    assert!(node.is_registered());
+    assert_eq!(node.value(), 5);
+}
+
+#[test]
+fn derived() {
+    let mut leaf = Leaf::new(2, 1);
+
+    assert_eq!(leaf.value(), 20);
+    assert_eq!(leaf.depth(), 1);
+
+    leaf.as_mut().base_node_mut().set_value(3);
+    assert_eq!(leaf.value(), 40);
+}
+
+#[test]
+fn secondary() {
+    let mut named = NamedNode::new(1);
+
+    assert_eq!(named.id(), 2);
+    assert_eq!(named.value(), 1);
+
+    named.as_mut().set_value(3);
+    assert_eq!(named.value(), 6);
+}
+
+#[test]
+fn in_place_argument_names() {
+    let mut slot = std::mem::MaybeUninit::<Cell>::uninit();
+    let cell = unsafe {
+        Cell::new_in_place(slot.as_mut_ptr(), 6);
+        slot.assume_init_ref()
+    };
+
+    assert_eq!(cell.slot(), 6);
+}
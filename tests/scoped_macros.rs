@@ -0,0 +1,95 @@
+use vtable_gen::cpp_class;
+
+use crate::ui::{Widget, WidgetVirtuals};
+
+mod ui {
+    use vtable_gen::cpp_class;
+
+    cpp_class! {
+        #[derive(Default)]
+        #[gen_vtable(no_unimpl)]
+        pub struct Widget {
+            virtual pub fn draw(&self) -> u32
+        }
+    }
+
+    impl WidgetVirtuals for Widget {
+        extern "C" fn draw(_this: &Widget) -> u32 {
+            1
+        }
+    }
+
+    // identical to the panel of `net`
+    cpp_class! {
+        #[derive(Default)]
+        #[gen_vtable(no_unimpl)]
+        pub struct Panel {
+            virtual pub fn id(&self) -> u32
+        }
+    }
+
+    impl PanelVirtuals for Panel {
+        extern "C" fn id(_this: &Panel) -> u32 {
+            1
+        }
+    }
+}
+
+mod net {
+    use vtable_gen::cpp_class;
+
+    cpp_class! {
+        #[derive(Default)]
+        #[gen_vtable(no_unimpl)]
+        pub struct Widget {
+            virtual pub fn send(&self) -> u32
+        }
+    }
+
+    impl WidgetVirtuals for Widget {
+        extern "C" fn send(_this: &Widget) -> u32 {
+            2
+        }
+    }
+
+    cpp_class! {
+        #[derive(Default)]
+        #[gen_vtable(no_unimpl)]
+        pub struct Panel {
+            virtual pub fn id(&self) -> u32
+        }
+    }
+
+    impl PanelVirtuals for Panel {
+        extern "C" fn id(_this: &Panel) -> u32 {
+            2
+        }
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct Button: ui::Widget {}
+}
+
+impl WidgetVirtuals for Button {
+    extern "C" fn draw(_this: &Widget) -> u32 {
+        3
+    }
+}
+
+impl ButtonVirtuals for Button {}
+
+#[test]
+fn default() {
+    assert_eq!(ui::Widget::default().draw(), 1);
+    assert_eq!(net::Widget::default().send(), 2);
+    assert_eq!(Button::default().draw(), 3);
+}
+
+#[test]
+fn identical_classes() {
+    assert_eq!(ui::Panel::default().id(), 1);
+    assert_eq!(net::Panel::default().id(), 2);
+}
@@ -0,0 +1,169 @@
+use vtable_gen::cpp_class;
+
+use crate::shapes::{AVirtuals, BVirtuals, CVirtuals};
+
+mod shapes {
+    use vtable_gen::cpp_class;
+
+    cpp_class! {
+        #[derive(Default)]
+        #[gen_vtable]
+        pub struct A {
+            pub a: u32,
+
+            virtual pub fn a(&self) -> u32
+        }
+    }
+
+    cpp_class! {
+        #[derive(Default)]
+        #[gen_vtable]
+        pub struct B {
+            pub b: u32,
+
+            virtual pub fn b(&self) -> u32
+        }
+    }
+
+    cpp_class! {
+        #[derive(Default)]
+        #[gen_vtable]
+        pub struct C: A, B {
+            virtual pub fn c(&self) -> u32
+        }
+    }
+}
+
+// `B` is found through `C` without a `gen_base`
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable]
+    struct D: shapes::C {
+        virtual pub fn d(&self) -> u32
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct E: D {
+        e: u32,
+    }
+}
+
+impl AVirtuals for E {
+    extern "C" fn a(this: &shapes::A) -> u32 {
+        this.a + 1
+    }
+}
+
+impl BVirtuals for E {
+    extern "C" fn b(this: &shapes::B) -> u32 {
+        this.b + 2
+    }
+}
+
+impl CVirtuals for E {
+    extern "C" fn c(_this: &shapes::C) -> u32 {
+        3
+    }
+}
+
+impl DVirtuals for E {
+    extern "C" fn d(_this: &D) -> u32 {
+        4
+    }
+}
+
+impl EVirtuals for E {}
+
+// `gen_base` still overrides the inherited bases
+cpp_class! {
+    #[derive(Default)]
+    #[gen_base(shapes::C = [shapes::B])]
+    #[gen_vtable(no_unimpl)]
+    struct F: shapes::C {}
+}
+
+impl AVirtuals for F {
+    extern "C" fn a(_this: &shapes::A) -> u32 {
+        5
+    }
+}
+
+impl BVirtuals for F {
+    extern "C" fn b(_this: &shapes::B) -> u32 {
+        6
+    }
+}
+
+impl CVirtuals for F {
+    extern "C" fn c(_this: &shapes::C) -> u32 {
+        7
+    }
+}
+
+impl FVirtuals for F {}
+
+// the override replaces the inherited bases rather than adding to them
+cpp_class! {
+    #[derive(Default)]
+    #[gen_base(shapes::C = [crate::shapes::B])]
+    #[gen_vtable(no_unimpl)]
+    struct G: shapes::C {}
+}
+
+impl AVirtuals for G {
+    extern "C" fn a(_this: &shapes::A) -> u32 {
+        8
+    }
+}
+
+impl BVirtuals for G {
+    extern "C" fn b(_this: &shapes::B) -> u32 {
+        9
+    }
+}
+
+impl CVirtuals for G {
+    extern "C" fn c(_this: &shapes::C) -> u32 {
+        10
+    }
+}
+
+impl GVirtuals for G {}
+
+#[test]
+fn layout() {
+    assert_eq!(std::mem::size_of::<E>(), std::mem::size_of::<usize>() * 5);
+    assert_eq!(std::mem::size_of::<F>(), std::mem::size_of::<usize>() * 4);
+    assert_eq!(std::mem::size_of::<G>(), std::mem::size_of::<usize>() * 4);
+}
+
+#[test]
+fn inherited() {
+    let e = E::default();
+
+    assert_eq!(e.a(), 1);
+    assert_eq!(<shapes::C as AsRef<shapes::B>>::as_ref(&e).b(), 2);
+    assert_eq!(e.c(), 3);
+    assert_eq!(e.d(), 4);
+}
+
+#[test]
+fn overridden() {
+    let f = F::default();
+
+    assert_eq!(f.a(), 5);
+    assert_eq!(<shapes::C as AsRef<shapes::B>>::as_ref(&f).b(), 6);
+    assert_eq!(f.c(), 7);
+}
+
+#[test]
+fn overridden_with_other_paths() {
+    let g = G::default();
+
+    assert_eq!(g.a(), 8);
+    assert_eq!(<shapes::C as AsRef<shapes::B>>::as_ref(&g).b(), 9);
+    assert_eq!(g.c(), 10);
+}
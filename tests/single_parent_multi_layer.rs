@@ -6,7 +6,7 @@ cpp_class! {
     struct Foo {
         a: f32,
 
-        virtual(1) extern "fastcall" fn func(&self, a: u32, b: f32) -> usize,
+        virtual(1) extern "system" fn func(&self, a: u32, b: f32) -> usize,
     }
 
     impl Foo {
@@ -18,7 +18,7 @@ cpp_class! {
 }
 
 impl FooVirtuals for Foo {
-    extern "fastcall" fn func(this: &Foo, a: u32, b: f32) -> usize {
+    extern "system" fn func(this: &Foo, a: u32, b: f32) -> usize {
         this.a as usize + a as usize + b as usize
     }
 }
@@ -43,7 +43,7 @@ cpp_class! {
 }
 
 impl FooVirtuals for Bar {
-    extern "fastcall" fn func(this: &Foo, a: u32, b: f32) -> usize {
+    extern "system" fn func(this: &Foo, a: u32, b: f32) -> usize {
         this.a as usize + a as usize + b as usize + b as usize
     }
 }
@@ -74,7 +74,7 @@ cpp_class! {
 }
 
 impl FooVirtuals for Baz {
-    extern "fastcall" fn func(this: &Foo, a: u32, b: f32) -> usize {
+    extern "system" fn func(this: &Foo, a: u32, b: f32) -> usize {
         this.a as usize + a as usize + b as usize + b as usize + b as usize
     }
 }
@@ -123,7 +123,7 @@ fn unimpl_method() {
     let b = Baz::new(2.5, 3.5, 4.5);
 
     // ensure that unimplemented methods panic
-    (unsafe { &*(b.vfptr as *const FooVTable) }.unimpl_0)()
+    (b.vfptr.unimpl_0)()
 }
 
 #[test]
@@ -0,0 +1,100 @@
+use vtable_gen::cpp_class;
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct A {
+        a: u32,
+
+        virtual fn a(&self) -> u32
+    }
+}
+
+impl AVirtuals for A {
+    extern "C" fn a(this: &A) -> u32 {
+        this.a
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct B: A {
+        b: u32
+    }
+}
+
+impl AVirtuals for B {
+    extern "C" fn a(this: &A) -> u32 {
+        this.a + 1
+    }
+}
+
+impl BVirtuals for B {}
+
+// the VTables of generic classes are registered, which needs `alloc`
+#[cfg(feature = "alloc")]
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct G<T: Default> {
+        g: T,
+
+        virtual fn g(&self) -> u32
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Default> GVirtuals<T> for G<T> {
+    extern "C" fn g(_this: &G<T>) -> u32 {
+        std::mem::size_of::<T>() as u32
+    }
+}
+
+#[test]
+fn unique() {
+    assert!(std::ptr::eq(A::vtbl_for_a(), A::vtbl_for_a()));
+    assert!(std::ptr::eq(
+        A::default().vfptr.as_ptr(),
+        A::default().vfptr.as_ptr()
+    ));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn unique_generic() {
+    assert!(std::ptr::eq(G::<u32>::vtbl_for_g(), G::<u32>::vtbl_for_g()));
+    assert!(std::ptr::eq(
+        G::<u32>::default().vfptr.as_ptr(),
+        G::<u32>::vtbl_for_g()
+    ));
+    assert!(!std::ptr::eq(
+        G::<u32>::vtbl_for_g() as *const _ as *const (),
+        G::<u64>::vtbl_for_g() as *const _ as *const ()
+    ));
+}
+
+#[test]
+fn is() {
+    let a = A::default();
+    let b = B::default();
+
+    assert!(a.is::<A>());
+    assert!(!a.is::<B>());
+    assert!(b.is::<B>());
+    assert!(!b.is::<A>());
+
+    // the test works through a reference to the base
+    let base: &A = &b;
+    assert!(base.is::<B>());
+    assert_eq!(base.a(), 1);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn is_generic() {
+    let g = G::<u32>::default();
+    assert!(g.is::<G<u32>>());
+    assert!(!g.is::<G<u64>>());
+    assert_eq!(g.g(), 4);
+}
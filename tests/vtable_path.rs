@@ -0,0 +1,79 @@
+use vtable_gen::cpp_class;
+
+use crate::sdk::{A, AVirtuals, B, BVirtuals};
+
+mod sdk {
+    vtable_gen::define_cpp_class!(sdk_class, "crate::sdk");
+
+    sdk_class! {
+        #[derive(Default)]
+        #[gen_vtable(no_unimpl)]
+        pub struct A {
+            virtual pub fn a(&self) -> u32
+        }
+    }
+
+    impl AVirtuals for A {
+        extern "C" fn a(_this: &A) -> u32 {
+            1
+        }
+    }
+
+    sdk_class! {
+        #[derive(Default)]
+        #[gen_vtable]
+        pub struct B: A {
+            virtual pub fn b(&self) -> u32
+        }
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable]
+    struct C: B {
+        virtual fn c(&self) -> u32
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[vtable_path = "crate"]
+    #[gen_vtable(no_unimpl)]
+    struct D: A {
+        virtual fn d(&self) -> u32
+    }
+}
+
+impl AVirtuals for D {
+    extern "C" fn a(_this: &A) -> u32 {
+        2
+    }
+}
+
+impl DVirtuals for D {
+    extern "C" fn d(_this: &D) -> u32 {
+        3
+    }
+}
+
+#[test]
+fn default() {
+    let a = A::default();
+    assert_eq!(a.a(), 1);
+
+    let d = D::default();
+    assert_eq!(d.a(), 2);
+    assert_eq!(d.d(), 3);
+}
+
+#[test]
+fn unimpl() {
+    // `B` and `C` are implemented through the exported unimpl macros
+    let c = C::default();
+    assert!(c.is::<C>());
+    assert_eq!(
+        std::mem::size_of::<CVTable>(),
+        std::mem::size_of::<usize>() * 3
+    );
+}
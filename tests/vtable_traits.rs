@@ -0,0 +1,91 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use vtable_gen::cpp_class;
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct A {
+        virtual fn a(&self) -> u32
+    }
+}
+
+impl AVirtuals for A {
+    extern "C" fn a(_this: &A) -> u32 {
+        1
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct B: A {
+        virtual fn b(&self) -> u32
+    }
+}
+
+impl AVirtuals for B {
+    extern "C" fn a(_this: &A) -> u32 {
+        2
+    }
+}
+
+impl BVirtuals for B {
+    extern "C" fn b(_this: &B) -> u32 {
+        3
+    }
+}
+
+fn hash_of(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn eq() {
+    assert_eq!(A::vtbl_for_a(), A::vtbl_for_a());
+    assert_eq!(B::vtbl_for_b(), B::vtbl_for_b());
+    assert_ne!(A::vtbl_for_a(), &B::vtbl_for_b().base_a);
+}
+
+#[test]
+fn hash() {
+    assert_eq!(hash_of(A::vtbl_for_a()), hash_of(A::vtbl_for_a()));
+    assert_ne!(hash_of(A::vtbl_for_a()), hash_of(&B::vtbl_for_b().base_a));
+}
+
+#[test]
+fn debug() {
+    let debug = format!("{:?}", B::vtbl_for_b());
+
+    assert!(debug.starts_with("B {"));
+    assert!(debug.contains("base_a: A { a: 0x"));
+    assert!(debug.contains("b: 0x"));
+}
+
+// symbols are resolved through the dynamic linker, which needs `std`
+#[cfg(all(target_os = "linux", feature = "std"))]
+#[test]
+fn debug_symbols() {
+    extern "C" {
+        fn getpid() -> i32;
+    }
+
+    // exported functions are named by the dynamic linker. the slots are never called
+    let getpid = getpid as unsafe extern "C" fn() -> i32 as usize;
+    let vtbl = AVTable {
+        a: unsafe { std::mem::transmute::<usize, extern "C" fn(&A) -> u32>(getpid) },
+    };
+    let debug = format!("{vtbl:?}");
+    assert!(debug.starts_with("A { a: 0x"), "{debug}");
+    assert!(debug.ends_with("getpid) }"), "{debug}");
+
+    // addresses inside a function are named by their offset from it
+    let vtbl = AVTable {
+        a: unsafe { std::mem::transmute::<usize, extern "C" fn(&A) -> u32>(getpid + 1) },
+    };
+    let debug = format!("{vtbl:?}");
+    assert!(debug.ends_with("getpid+0x1) }"), "{debug}");
+}
@@ -0,0 +1,179 @@
+#![cfg(feature = "alloc")]
+
+use std::fmt::Display;
+
+use vtable_gen::cpp_class;
+
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Label<T> where T: Display {
+        value: T,
+
+        virtual fn width(&self) -> usize
+    }
+
+    impl<T> Label<T> where T: Display {
+        fn new(value: T) -> Self {
+            Self { value }
+        }
+    }
+}
+
+impl<T: Display> LabelVirtuals<T> for Label<T> {
+    extern "C" fn width(this: &Label<T>) -> usize {
+        this.value.to_string().len()
+    }
+}
+
+// the where clause may also follow the bases, and its predicates can use default parameters
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct Padded<T, P = u8>: Label<T> where T: Display, P: Into<usize> + Copy {
+        padding: P,
+
+        virtual fn padded_width(&self) -> usize
+    }
+
+    impl<T, P> Padded<T, P> where T: Display, P: Into<usize> + Copy {
+        fn new(value: T, padding: P) -> Self {
+            Self {
+                base_label: Label::new(value),
+                padding,
+            }
+        }
+    }
+}
+
+impl<T: Display, P: Into<usize> + Copy> LabelVirtuals<T> for Padded<T, P> {
+    extern "C" fn width(this: &Label<T>) -> usize {
+        this.value.to_string().len()
+    }
+}
+
+impl<T: Display, P: Into<usize> + Copy> PaddedVirtuals<T, P> for Padded<T, P> {
+    extern "C" fn padded_width(this: &Padded<T, P>) -> usize {
+        this.width() + this.padding.into()
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct Buffer<T: Default + Copy, const N: usize> {
+        first: T,
+
+        virtual fn capacity(&self) -> usize
+    }
+}
+
+impl<T: Default + Copy, const N: usize> BufferVirtuals<T, N> for Buffer<T, N> {
+    extern "C" fn capacity(_this: &Buffer<T, N>) -> usize {
+        N
+    }
+}
+
+// base arguments may be const expressions, and types built from the generics of the class
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct Bytes: Buffer<u8, { 2 * 8 }> {}
+}
+
+impl BufferVirtuals<u8, 16> for Bytes {
+    extern "C" fn capacity(_this: &Buffer<u8, 16>) -> usize {
+        16
+    }
+}
+
+impl BytesVirtuals for Bytes {}
+
+// or come before them
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct Slots<T, const N: usize> where T: Default + Copy: Buffer<Option<T>, { N }> {}
+}
+
+impl<T: Default + Copy, const N: usize> BufferVirtuals<Option<T>, N> for Slots<T, N> {
+    extern "C" fn capacity(_this: &Buffer<Option<T>, N>) -> usize {
+        N * 2
+    }
+}
+
+impl<T: Default + Copy, const N: usize> SlotsVirtuals<T, N> for Slots<T, N> {}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct Table<const N: usize>: Slots<u32, N> {
+        virtual fn rows(&self) -> usize
+    }
+}
+
+impl<const N: usize> BufferVirtuals<Option<u32>, N> for Table<N> {
+    extern "C" fn capacity(_this: &Buffer<Option<u32>, N>) -> usize {
+        N * 3
+    }
+}
+
+impl<const N: usize> SlotsVirtuals<u32, N> for Table<N> {}
+
+impl<const N: usize> TableVirtuals<N> for Table<N> {
+    extern "C" fn rows(this: &Table<N>) -> usize {
+        this.capacity() / 3
+    }
+}
+
+// bounds on the class's lifetimes are left off the VTable
+cpp_class! {
+    #[gen_vtable(no_unimpl)]
+    struct View<'a, T: 'a> where T: Display + 'a, &'a T: Copy {
+        item: &'a T,
+
+        virtual fn width(&self) -> usize
+    }
+
+    impl<'a, T> View<'a, T> where T: Display {
+        fn new(item: &'a T) -> Self {
+            Self { item }
+        }
+    }
+}
+
+impl<T: Display> ViewVirtuals<T> for View<'_, T> {
+    extern "C" fn width(this: &View<T>) -> usize {
+        this.item.to_string().len()
+    }
+}
+
+#[test]
+fn where_clause() {
+    let label = Label::new(1234);
+    let view = View::new(&label.value);
+
+    assert_eq!(label.width(), 4);
+    assert_eq!(view.width(), 4);
+}
+
+#[test]
+fn default_param() {
+    let padded: Padded<&str> = Padded::new("abc", 2);
+
+    assert_eq!(padded.width(), 3);
+    assert_eq!(padded.padded_width(), 5);
+}
+
+#[test]
+fn const_expression() {
+    assert_eq!(Bytes::default().capacity(), 16);
+    assert_eq!(Bytes::default().first, 0);
+}
+
+#[test]
+fn derived_generic_args() {
+    assert_eq!(Slots::<u8, 4>::default().capacity(), 8);
+
+    let table = Table::<5>::default();
+    assert_eq!(table.capacity(), 15);
+    assert_eq!(table.rows(), 5);
+}
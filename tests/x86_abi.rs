@@ -0,0 +1,61 @@
+// the non-default calling conventions of 32-bit x86, which other targets reject
+#![cfg(target_arch = "x86")]
+
+use vtable_gen::cpp_class;
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl, abi = "stdcall")]
+    struct Foo {
+        a: u32,
+
+        virtual fn get(&self) -> u32,
+        virtual(2) extern "fastcall" fn func(&self, a: u32, b: f32) -> usize
+    }
+}
+
+impl FooVirtuals for Foo {
+    extern "stdcall" fn get(this: &Foo) -> u32 {
+        this.a
+    }
+
+    extern "fastcall" fn func(this: &Foo, a: u32, b: f32) -> usize {
+        this.a as usize + a as usize + b as usize
+    }
+}
+
+cpp_class! {
+    #[derive(Default)]
+    #[gen_vtable(no_unimpl)]
+    struct Bar: Foo {
+        virtual fn bar(&self) -> u32
+    }
+}
+
+impl FooVirtuals for Bar {
+    extern "stdcall" fn get(this: &Foo) -> u32 {
+        this.a + 1
+    }
+
+    extern "fastcall" fn func(_this: &Foo, a: u32, _b: f32) -> usize {
+        a as usize
+    }
+}
+
+impl BarVirtuals for Bar {
+    extern "stdcall" fn bar(_this: &Bar) -> u32 {
+        3
+    }
+}
+
+#[test]
+fn calling_conventions() {
+    let foo = Foo::default();
+    let bar = Bar::default();
+
+    assert_eq!(foo.get(), 0);
+    assert_eq!(foo.func(1, 2.0), 3);
+    assert_eq!(bar.get(), 1);
+    assert_eq!(bar.func(4, 2.0), 4);
+    assert_eq!(bar.bar(), 3);
+}
@@ -0,0 +1,215 @@
+use itertools::Itertools;
+use quote::{format_ident, quote};
+use syn::File;
+
+use crate::class::make_base_name;
+use crate::parse::ItemClass;
+use crate::util::{borrow_base, extract_ident};
+
+/// Implements anything necessary for base classes. Mutable access to the bases of `pinned` classes
+/// goes through pinned references.
+pub fn gen_base_helpers(class: &ItemClass, pinned: bool) -> File {
+    // do the things
+    let deref = impl_deref(class, pinned);
+    let as_ref = impl_as_ref(class, pinned);
+    let from_base = impl_from_base(class);
+    let pin_projections = pinned.then(|| impl_pin_projections(class));
+
+    syn::parse(
+        quote! {
+            #deref
+            #as_ref
+            #from_base
+            #pin_projections
+        }
+        .into(),
+    )
+    .expect("failed to generate bases")
+}
+
+// implement `AsRef` for all bases, and `AsMut` unless the class is pinned.
+fn impl_as_ref(class: &ItemClass, pinned: bool) -> File {
+    let ident = &class.ident;
+    let (impl_generics, _, where_clause) = class.generics.split_for_impl();
+    let generic_args = class.generic_args();
+    let base_paths = class
+        .bases
+        .bases
+        .iter()
+        .map(|(base, _)| base.clone())
+        .collect_vec();
+    let base_names = base_paths
+        .iter()
+        .map(|path| make_base_name(extract_ident(path)))
+        .collect_vec();
+    let borrows = base_names
+        .iter()
+        .map(|base_name| borrow_base(quote!(self), quote!(.#base_name), false))
+        .collect_vec();
+    let mut_borrows = base_names
+        .iter()
+        .map(|base_name| borrow_base(quote!(self), quote!(.#base_name), true))
+        .collect_vec();
+    let as_mut = (!pinned).then(|| {
+        quote! {
+            #(
+                impl #impl_generics ::core::convert::AsMut<#base_paths> for #ident #generic_args #where_clause {
+                    fn as_mut(&mut self) -> &mut #base_paths {
+                        unsafe { #mut_borrows }
+                    }
+                }
+            )*
+        }
+    });
+
+    syn::parse(
+        quote! {
+            #(
+                impl #impl_generics ::core::convert::AsRef<#base_paths> for #ident #generic_args #where_clause {
+                    fn as_ref(&self) -> &#base_paths {
+                        unsafe { #borrows }
+                    }
+                }
+            )*
+            #as_mut
+        }
+        .into(),
+    )
+    .expect("failed to generate `AsRef` impl")
+}
+
+// implement `Deref` for the primary base, and `DerefMut` unless the class is pinned.
+fn impl_deref(class: &ItemClass, pinned: bool) -> Option<File> {
+    let ident = &class.ident;
+    let (impl_generics, _, where_clause) = class.generics.split_for_impl();
+    let generic_args = class.generic_args();
+    let (base_path, _) = class.bases.bases.first()?;
+    let base_ident = make_base_name(extract_ident(base_path));
+    let borrow = borrow_base(quote!(self), quote!(.#base_ident), false);
+    let mut_borrow = borrow_base(quote!(self), quote!(.#base_ident), true);
+    let deref_mut = (!pinned).then(|| {
+        quote! {
+            impl #impl_generics ::core::ops::DerefMut for #ident #generic_args #where_clause {
+                fn deref_mut(&mut self) -> &mut Self::Target {
+                    unsafe { #mut_borrow }
+                }
+            }
+        }
+    });
+
+    Some(
+        syn::parse(
+            quote! {
+                impl #impl_generics ::core::ops::Deref for #ident #generic_args #where_clause {
+                    type Target = #base_path;
+                    fn deref(&self) -> &Self::Target {
+                        unsafe { #borrow }
+                    }
+                }
+
+                #deref_mut
+            }
+            .into(),
+        )
+        .expect("failed to generate `Deref` impl"),
+    )
+}
+
+// implement projections from a pinned class to each of its bases.
+fn impl_pin_projections(class: &ItemClass) -> File {
+    let ident = &class.ident;
+    let (impl_generics, _, where_clause) = class.generics.split_for_impl();
+    let generic_args = class.generic_args();
+    let base_paths = class.bases.paths().collect_vec();
+    let base_names = base_paths
+        .iter()
+        .map(|path| make_base_name(extract_ident(path)))
+        .collect_vec();
+    let fn_idents = base_names
+        .iter()
+        .map(|base_name| format_ident!("{base_name}_mut"))
+        .collect_vec();
+    let borrows = base_names
+        .iter()
+        .map(|base_name| borrow_base(quote!(this), quote!(.#base_name), true))
+        .collect_vec();
+
+    syn::parse(
+        quote! {
+            impl #impl_generics #ident #generic_args #where_clause {
+                #(
+                    /// Projects a pinned mutable reference to the class onto a base.
+                    pub fn #fn_idents(
+                        self: ::core::pin::Pin<&mut Self>,
+                    ) -> ::core::pin::Pin<&mut #base_paths> {
+                        // bases are never moved out of the class
+                        unsafe { self.map_unchecked_mut(|this| #borrows) }
+                    }
+                )*
+            }
+        }
+        .into(),
+    )
+    .expect("failed to generate pin projections")
+}
+
+// implement conversions from each base back to the class. The bases the class hands out are
+// borrowed through a pointer to the whole class, so they can be converted back.
+fn impl_from_base(class: &ItemClass) -> File {
+    let ident = &class.ident;
+    let (impl_generics, _, where_clause) = class.generics.split_for_impl();
+    let generic_args = class.generic_args();
+    let base_paths = class.bases.paths().collect_vec();
+    let base_names = base_paths
+        .iter()
+        .map(|path| make_base_name(extract_ident(path)))
+        .collect_vec();
+    let fn_idents = base_names
+        .iter()
+        .map(|base_name| format_ident!("from_{base_name}"))
+        .collect_vec();
+    let fn_mut_idents = base_names
+        .iter()
+        .map(|base_name| format_ident!("from_{base_name}_mut"))
+        .collect_vec();
+
+    syn::parse(
+        quote! {
+            impl #impl_generics #ident #generic_args #where_clause {
+                #(
+                    /// Converts a reference to a base back into a reference to the class. This is
+                    /// how overrides reach the class from the `this` their slot receives.
+                    ///
+                    /// # Safety
+                    /// `base` must be the base of an instance of the class, borrowed through a
+                    /// pointer to the whole instance. The bases handed out by the class are: those
+                    /// of `AsRef`, `Deref` and the pin projections, the receivers of forwarded
+                    /// virtuals, and the `this` of slots called through the vtable. Borrowing the
+                    /// base field directly, as in `&instance.base`, isn't.
+                    pub unsafe fn #fn_idents<'__base>(base: &'__base #base_paths) -> &'__base Self {
+                        &*(base as *const #base_paths)
+                            .byte_sub(::core::mem::offset_of!(Self, #base_names))
+                            .cast::<Self>()
+                    }
+
+                    /// Converts a mutable reference to a base back into a mutable reference to
+                    /// the class.
+                    ///
+                    /// # Safety
+                    /// `base` must be the base of an instance of the class, mutably borrowed
+                    /// through a pointer to the whole instance, as by `AsMut`, `DerefMut` and the
+                    /// pin projections. See the shared conversion.
+                    pub unsafe fn #fn_mut_idents<'__base>(
+                        base: &'__base mut #base_paths,
+                    ) -> &'__base mut Self {
+                        &mut *(base as *mut #base_paths)
+                            .byte_sub(::core::mem::offset_of!(Self, #base_names))
+                            .cast::<Self>()
+                    }
+                )*
+            }
+        }
+        .into(),
+    )
+    .expect("failed to generate base conversions")
+}
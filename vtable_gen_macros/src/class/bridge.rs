@@ -0,0 +1,298 @@
+use convert_case::{Case, Casing};
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{File, FnArg, ItemFn, ItemImpl, parse_quote, Pat, Path, Token};
+use syn::punctuated::Punctuated;
+use syn::visit_mut::VisitMut;
+
+use crate::class::helper_macro::Helper;
+use crate::class::make_base_name;
+use crate::class::vtable::make_vtable_ident;
+use crate::parse::{ItemClass, Virtual};
+use crate::util::{
+    borrow_base, extract_ident, extract_implementor_generics, last_segment, LifetimeEliser,
+    make_base_macro_path, make_base_module, make_generic_matchers, make_macro_prefix,
+    qualify_base_module, qualify_path, replace_crate_paths, replace_generics_with_descriptors,
+    strip_lifetimes,
+};
+
+/// Generates a bridge between a class and its virtuals, and forwards the virtuals of its
+/// secondary bases. Virtuals of `pinned` classes that take `&mut self` take a pinned reference
+/// instead.
+pub fn gen_bridge(class: &ItemClass, prefixed_bases: &[Path], pinned: bool) -> File {
+    let bridge = gen_direct_bridge(class, pinned);
+    let secondary_bridges = gen_secondary_bridges(class, prefixed_bases);
+
+    syn::parse(
+        quote! {
+            #bridge
+            #(#secondary_bridges)*
+        }
+        .into(),
+    )
+    .expect("failed to generate bridges")
+}
+
+/// Generates direct functions that call the class's virtuals through its vtable.
+fn gen_direct_bridge(class: &ItemClass, pinned: bool) -> ItemImpl {
+    let ident = &class.ident;
+    let generic_args = class.generic_args();
+    let vtable_generic_args = class.vtable_generic_args();
+    let vtable_ident = make_vtable_ident(&class.ident);
+
+    // generate direct functions
+    let mut fns: Vec<ItemFn> = Vec::new();
+    for virt in class.body.virtuals.iter() {
+        let mut arg_names = collect_arg_names(virt);
+
+        let attrs = &virt.attrs;
+        let vis = &virt.vis;
+        let unsafety = &virt.sig.unsafety;
+        let ident = &virt.sig.ident;
+        let mut args = virt.sig.inputs.clone();
+        let output = &virt.sig.output;
+
+        // use the receiver's own `self`, which shares the hygiene of the signature
+        let self_token = match args.first() {
+            Some(FnArg::Receiver(receiver)) => receiver.self_token,
+            _ => panic!("virtuals must take `&self` or `&mut self`"),
+        };
+
+        // the vtable takes the reference the pinned one wraps
+        let unpin = (pinned && pin_receiver(&mut args)).then(|| {
+            arg_names[0] = Ident::new("this", self_token.span);
+            quote!(let this = unsafe { ::core::pin::Pin::get_unchecked_mut(#self_token) };)
+        });
+        let receiver = &arg_names[0];
+
+        fns.push(parse_quote! {
+            #(#attrs)*
+            #vis #unsafety fn #ident (#args) #output {
+                #unpin
+                let vtbl = unsafe { &*(#receiver.vfptr.as_ptr() as *const #vtable_ident #vtable_generic_args) };
+                (vtbl.#ident)(#(#arg_names),*)
+            }
+        });
+    }
+
+    // derived classes reach the test through their primary base
+    if class.bases.is_empty() {
+        fns.push(parse_quote! {
+            /// Returns true if the object's vtable is exactly the primary vtable of the given class.
+            pub fn is<__Class: ::vtable_gen::CppClass>(&self) -> bool {
+                ::core::ptr::eq(
+                    self.vfptr.as_ptr() as *const (),
+                    __Class::vtable() as *const _ as *const (),
+                )
+            }
+        });
+    }
+
+    let (impl_generics, _, where_clause) = class.generics.split_for_impl();
+    syn::parse(
+        quote! {
+            impl #impl_generics #ident #generic_args #where_clause {
+                #(#fns)*
+            }
+        }
+        .into(),
+    )
+    .expect("failed to generate direct bridge")
+}
+
+/// Generates the helper macro arms that forward the class's virtuals, and those of its bases, from
+/// an implementor that contains the class as a secondary base.
+pub fn gen_bridge_macro(class: &ItemClass, pinned: bool, prefix: &TokenStream) -> TokenStream {
+    // collect all generic args into descriptors
+    let def_generic_matchers = make_generic_matchers(class);
+
+    let prefix = make_macro_prefix(prefix);
+    let method_prefix = class.ident.to_string().to_case(Case::Snake);
+    let forwarders = gen_forwarders(class, None, pinned);
+    let prefixed_forwarders = gen_forwarders(class, Some(&method_prefix), pinned);
+    let base_bridges = gen_base_bridges(class, &prefix, false);
+    let prefixed_base_bridges = gen_base_bridges(class, &prefix, true);
+
+    let selector = Helper::Bridge.selector();
+    let forwarders = replace_crate_paths(quote!(#(#forwarders)*));
+    let prefixed_forwarders = replace_crate_paths(quote!(#(#prefixed_forwarders)*));
+    quote! {
+        // module: The path of the module the class lives in, relative to the caller.
+        // implementor_ty: The type of the implementor.
+        // impl_generics: The generics of the implementor's impl block.
+        // where_clause: The where clause of the implementor's impl block.
+        // field: The fields that lead from the implementor to this class.
+        // gen_x: The definition generic at position `x`.
+        (#selector [$($module:tt)*] $implementor_ty:ty, [$($impl_generics:tt)*], [$($where_clause:tt)*], [$($field:ident)*], <#(#def_generic_matchers),*>) => {
+            #[allow(dead_code)]
+            impl $($impl_generics)* $implementor_ty $($where_clause)* {
+                #forwarders
+            }
+
+            #(#base_bridges)*
+        };
+        // prefixes each forwarded virtual with the name of the class that declares it.
+        (#selector [$($module:tt)*] $implementor_ty:ty, [$($impl_generics:tt)*], [$($where_clause:tt)*], [$($field:ident)*], <#(#def_generic_matchers),*>, prefixed) => {
+            #[allow(dead_code)]
+            impl $($impl_generics)* $implementor_ty $($where_clause)* {
+                #prefixed_forwarders
+            }
+
+            #(#prefixed_base_bridges)*
+        };
+    }
+}
+
+/// Generates the bridge macro invocations that forward the virtuals of each base, which are
+/// reached through the base's field.
+fn gen_base_bridges(class: &ItemClass, prefix: &TokenStream, prefixed: bool) -> Vec<TokenStream> {
+    let prefixed = prefixed.then(|| quote!(, prefixed));
+    let selector = Helper::Bridge.selector();
+    class
+        .bases
+        .paths()
+        .map(|base_path| {
+            let base_ident = extract_ident(base_path);
+            let macro_path = qualify_path(prefix, &make_base_macro_path(base_path));
+            let base_module = qualify_base_module(prefix, base_path);
+            let base_field = make_base_name(base_ident);
+
+            // determine the position of each and extract it out of the parent definition
+            let base_def_args = extract_implementor_generics(class, base_path);
+            quote! {
+                #macro_path!(
+                    #selector
+                    [#base_module]
+                    $implementor_ty,
+                    [$($impl_generics)*],
+                    [$($where_clause)*],
+                    [$($field)* #base_field],
+                    <#(#base_def_args),*>
+                    #prefixed
+                );
+            }
+        })
+        .collect()
+}
+
+/// Generates the forwarding functions for the class's virtuals, as emitted in the bridge macro.
+fn gen_forwarders(class: &ItemClass, prefix: Option<&str>, pinned: bool) -> Vec<TokenStream> {
+    class
+        .body
+        .virtuals
+        .iter()
+        .map(|virt| {
+            // the receiver is the implementor itself
+            let mut arg_names = collect_arg_names(virt).into_iter();
+            let self_ident = arg_names.next();
+
+            let attrs = &virt.attrs;
+            let vis = &virt.vis;
+            let ident = &virt.sig.ident;
+            let mut sig = virt.sig.clone();
+            LifetimeEliser::new(class.lifetimes()).visit_signature_mut(&mut sig);
+            if let Some(prefix) = prefix {
+                sig.ident = format_ident!("{prefix}_{ident}");
+            }
+
+            // pinned references are projected onto the class
+            let mutable = matches!(
+                sig.inputs.first(),
+                Some(FnArg::Receiver(receiver)) if receiver.mutability.is_some()
+            );
+            let receiver = if pinned && pin_receiver(&mut sig.inputs) {
+                let borrow = borrow_base(quote!(this), quote!($(.$field)*), true);
+                quote! {
+                    unsafe {
+                        ::core::pin::Pin::map_unchecked_mut(#self_ident, |this| #borrow)
+                    }
+                }
+            } else {
+                let borrow = borrow_base(quote!(#self_ident), quote!($(.$field)*), mutable);
+                quote!(unsafe { #borrow })
+            };
+
+            replace_generics_with_descriptors(
+                class,
+                quote! {
+                    #(#attrs)*
+                    #vis #sig {
+                        #receiver.#ident(#(#arg_names),*)
+                    }
+                },
+            )
+        })
+        .collect()
+}
+
+/// Forwards the virtuals of each secondary base to the class, prefixing those of `prefixed_bases`.
+fn gen_secondary_bridges(class: &ItemClass, prefixed_bases: &[Path]) -> Vec<TokenStream> {
+    let ident = &class.ident;
+    let (impl_generics, _, where_clause) = class.generics.split_for_impl();
+    let generic_args = class.generic_args();
+    let selector = Helper::Bridge.selector();
+
+    class
+        .bases
+        .paths()
+        .skip(1)
+        .map(|base_path| {
+            let base_ident = extract_ident(base_path);
+            let macro_path = make_base_macro_path(base_path);
+            let base_module = make_base_module(base_path);
+            let base_field = make_base_name(base_ident);
+            let base_args = last_segment(&strip_lifetimes(base_path)).arguments.clone();
+            let base_args = if base_args.is_empty() {
+                quote!(<>)
+            } else {
+                quote!(#base_args)
+            };
+            let prefixed = prefixed_bases
+                .iter()
+                .any(|path| extract_ident(path) == base_ident)
+                .then(|| quote!(, prefixed));
+
+            quote! {
+                #macro_path!(#selector [#base_module] #ident #generic_args, [#impl_generics], [#where_clause], [#base_field], #base_args #prefixed);
+            }
+        })
+        .collect()
+}
+
+/// Replaces a `&mut self` receiver with a pinned one, returning whether it was replaced.
+fn pin_receiver(inputs: &mut Punctuated<FnArg, Token![,]>) -> bool {
+    let Some(FnArg::Receiver(receiver)) = inputs.first_mut() else {
+        return false;
+    };
+    if receiver.colon_token.is_some()
+        || receiver.reference.is_none()
+        || receiver.mutability.is_none()
+    {
+        return false;
+    }
+
+    let ty = &receiver.ty;
+    receiver.ty = parse_quote!(::core::pin::Pin<#ty>);
+    receiver.reference = None;
+    receiver.mutability = None;
+    receiver.colon_token = Some(Default::default());
+    true
+}
+
+/// Collects the names of the virtual's arguments.
+fn collect_arg_names(virt: &Virtual) -> Vec<Ident> {
+    virt.sig
+        .inputs
+        .iter()
+        .map(|arg| match arg {
+            FnArg::Receiver(receiver) => Ident::new("self", receiver.self_token.span),
+            FnArg::Typed(ty) => {
+                if let Pat::Ident(ident) = &*ty.pat {
+                    ident.ident.clone()
+                } else {
+                    panic!("virtual args must have identifiers")
+                }
+            }
+        })
+        .collect()
+}
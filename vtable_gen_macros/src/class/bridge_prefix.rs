@@ -0,0 +1,76 @@
+use std::collections::HashSet;
+
+use proc_macro2::Ident;
+use syn::{Attribute, Path, Token};
+use syn::punctuated::Punctuated;
+
+use crate::class::extractor::AttributeExtractor;
+use crate::class::inherit::Inherited;
+use crate::parse::ItemClass;
+use crate::util::extract_ident;
+
+/// Secondary bases whose bridged virtuals are prefixed with the declaring class's name.
+pub struct BridgePrefix;
+
+impl AttributeExtractor for BridgePrefix {
+    type Output = Vec<Path>;
+
+    fn attr() -> &'static str {
+        "bridge_prefix"
+    }
+
+    fn parse_attr(attr: Attribute) -> syn::Result<Self::Output> {
+        Ok(attr
+            .parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?
+            .into_iter()
+            .collect())
+    }
+}
+
+/// Prefixes the secondary bases with virtuals that are already reachable on the class, either
+/// declared by the class, reached through its primary base or forwarded from an earlier base.
+/// Returns the names of the virtuals that can be called on the class without a prefix.
+pub fn prefix_collisions(
+    class: &ItemClass,
+    inherited: &[Inherited],
+    prefixed_bases: &mut Vec<Path>,
+) -> Vec<Ident> {
+    let own = class.body.virtuals.iter().map(|virt| &virt.sig.ident);
+    let primary = inherited
+        .first()
+        .into_iter()
+        .flat_map(|primary| primary.virtuals.iter());
+    let mut names = HashSet::new();
+    let mut virtuals = Vec::new();
+    for virt in own.chain(primary) {
+        if names.insert(virt.to_string()) {
+            virtuals.push(virt.clone());
+        }
+    }
+
+    for (base_path, info) in class.bases.paths().zip(inherited).skip(1) {
+        let base_ident = extract_ident(base_path);
+        if prefixed_bases
+            .iter()
+            .any(|path| extract_ident(path) == base_ident)
+        {
+            continue;
+        }
+        if info
+            .virtuals
+            .iter()
+            .any(|virt| names.contains(&virt.to_string()))
+        {
+            prefixed_bases.push(base_path.clone());
+            continue;
+        }
+
+        for virt in &info.virtuals {
+            if names.insert(virt.to_string()) {
+                virtuals.push(virt.clone());
+            }
+        }
+    }
+
+    virtuals
+}
@@ -0,0 +1,94 @@
+use proc_macro2::Ident;
+use syn::{Attribute, FnArg, ImplItemFn, parse_quote, Path, ReturnType, Stmt};
+
+use crate::class::inherit::Inherited;
+use crate::class::make_base_name;
+use crate::parse::ItemClass;
+use crate::util::extract_ident;
+
+/// How instances of a class are destroyed through its vtable.
+pub enum Destructor {
+    /// Through the class's own virtual, marked `#[destructor]`.
+    Virtual(Ident),
+    /// Through the virtual destructor of a base.
+    Base(Path),
+}
+
+impl Destructor {
+    /// Extracts the class's virtual destructor, falling back to that of the first base with one.
+    pub fn extract(class: &mut ItemClass, inherited: &[Inherited]) -> Option<Self> {
+        let mut destructors = class.body.virtuals.iter_mut().filter_map(|virt| {
+            let len = virt.attrs.len();
+            virt.attrs
+                .retain(|attr| !attr.path().is_ident("destructor"));
+            (virt.attrs.len() != len).then_some(virt)
+        });
+
+        if let Some(virt) = destructors.next() {
+            let takes_mut_self = matches!(
+                virt.sig.inputs.first(),
+                Some(FnArg::Receiver(receiver)) if receiver.mutability.is_some()
+            );
+            if !takes_mut_self
+                || virt.sig.inputs.len() != 1
+                || virt.sig.output != ReturnType::Default
+            {
+                panic!("virtual destructors must take only `&mut self` and return nothing");
+            }
+
+            // the instance is gone afterwards, so only its owner may destroy it
+            virt.sig.unsafety = Some(Default::default());
+            virt.attrs.extend::<[Attribute; 3]>([
+                parse_quote!(#[doc = ""]),
+                parse_quote!(#[doc = " # Safety"]),
+                parse_quote!(#[doc = " The instance is destroyed, so it must not be used or dropped afterwards."]),
+            ]);
+
+            let ident = virt.sig.ident.clone();
+            if destructors.next().is_some() {
+                panic!("classes can only have one virtual destructor");
+            }
+            return Some(Self::Virtual(ident));
+        }
+
+        class
+            .bases
+            .paths()
+            .zip(inherited)
+            .find(|(_, inherited)| inherited.destructor)
+            .map(|(base_path, _)| Self::Base(base_path.clone()))
+    }
+
+    /// Returns the class's own virtual destructor, if it has one.
+    pub fn virtual_ident(&self) -> Option<&Ident> {
+        match self {
+            Self::Virtual(ident) => Some(ident),
+            Self::Base(_) => None,
+        }
+    }
+
+    /// Generates the `CppClass::destruct` override that destroys instances through the virtual
+    /// destructor.
+    pub fn gen_destruct(&self, pinned: bool) -> ImplItemFn {
+        let destroy: Stmt = match self {
+            Self::Virtual(ident) if pinned => parse_quote! {
+                Self::#ident(::core::pin::Pin::new_unchecked(&mut *this));
+            },
+            Self::Virtual(ident) => parse_quote!(Self::#ident(&mut *this);),
+            Self::Base(base_path) => {
+                let base_ident = make_base_name(extract_ident(base_path));
+                parse_quote! {
+                    <#base_path as ::vtable_gen::CppClass>::destruct(
+                        ::core::ptr::addr_of_mut!((*this).#base_ident),
+                    );
+                }
+            }
+        };
+
+        parse_quote! {
+            unsafe fn destruct(this: *mut Self) {
+                #destroy
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//!
+//! Copyright (C) Warsaw Revamped. Any unauthorized use, modification, or distribution of any portion of this file is prohibited. All rights reserved.
+//!
+
+use darling::{FromAttributes, FromMeta};
+use darling::util::Flag;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Attribute;
+
+use crate::class::extractor::AttributeExtractor;
+
+#[derive(FromAttributes)]
+#[darling(attributes(gen_vtable))]
+pub struct GenVTable {
+    pub no_unimpl: Flag,
+    pub abi: Option<String>,
+    pub on_panic: Option<OnPanic>,
+}
+
+/// What the VTable slots do when an implementation panics.
+#[derive(FromMeta, Clone, Copy, PartialEq, Eq)]
+#[darling(rename_all = "snake_case")]
+pub enum OnPanic {
+    Abort,
+    Default,
+    LogAndDefault,
+    Unwind,
+}
+
+impl OnPanic {
+    /// Returns the panic policy the slots are wrapped with, if any. Unwinding slots are called
+    /// directly, since they use the `C-unwind` ABI.
+    pub fn policy(self) -> Option<TokenStream> {
+        match self {
+            Self::Abort => Some(quote!(::vtable_gen::AbortOnPanic)),
+            Self::Default => Some(quote!(::vtable_gen::DefaultOnPanic)),
+            Self::LogAndDefault => Some(quote!(::vtable_gen::LogAndDefaultOnPanic)),
+            Self::Unwind => None,
+        }
+    }
+}
+
+impl AttributeExtractor for GenVTable {
+    type Output = Self;
+
+    fn attr() -> &'static str {
+        "gen_vtable"
+    }
+
+    fn parse_attr(attr: Attribute) -> syn::Result<Self::Output> {
+        Self::from_attributes(&[attr]).map_err(|err| syn::Error::new(Span::call_site(), err))
+    }
+}
@@ -0,0 +1,524 @@
+use std::collections::HashMap;
+use std::iter;
+
+use itertools::Itertools;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    Attribute, bracketed, File, GenericArgument, GenericParam, ItemImpl, ItemTrait, Lifetime,
+    Member, parse_quote, Path, PathArguments, Token, Type,
+};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::visit_mut::{self, VisitMut};
+
+use crate::class::{helper_macro, make_base_name, standardize_virtuals, trt};
+use crate::class::destructor::Destructor;
+use crate::class::extractor::AttributeExtractor;
+use crate::class::generic_base_name::GenericBaseName;
+use crate::class::helper_macro::Helper;
+use crate::class::trt::make_virtuals;
+use crate::class::vtable::make_vtable_ident;
+use crate::parse::{CppDef, ItemClass};
+use crate::util::{extract_ident, last_segment, make_base_macro_path, remove_punctuated};
+
+/// A generic base class replacement.
+#[derive(Clone)]
+pub struct GenericBase {
+    pub base: Ident,
+    pub eq: Token![=],
+    pub repl: Path,
+}
+
+impl AttributeExtractor for GenericBase {
+    type Output = Vec<Vec<GenericBase>>;
+
+    fn attr() -> &'static str {
+        "impl_generic_base"
+    }
+
+    fn parse_attr(attr: Attribute) -> syn::Result<Self::Output> {
+        Ok(attr
+            .parse_args_with(|args: ParseStream| {
+                args.parse_terminated(
+                    |base_collection| {
+                        let contents;
+                        bracketed!(contents in base_collection);
+
+                        contents.parse_terminated(Self::parse, Token![,])
+                    },
+                    Token![,],
+                )
+            })?
+            .into_iter()
+            .map(|p| p.into_iter().collect())
+            .collect())
+    }
+}
+
+impl Parse for GenericBase {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            base: input.parse()?,
+            eq: input.parse()?,
+            repl: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for GenericBase {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.base.to_tokens(tokens);
+        self.eq.to_tokens(tokens);
+        self.repl.to_tokens(tokens);
+    }
+}
+
+/// The virtuals trait of the family of an instantiation, which implementors can implement instead
+/// of the instantiation's own.
+pub struct VirtualsAlias;
+
+impl AttributeExtractor for VirtualsAlias {
+    type Output = Path;
+
+    fn attr() -> &'static str {
+        "virtuals_alias"
+    }
+
+    fn parse_attr(attr: Attribute) -> syn::Result<Self::Output> {
+        attr.parse_args()
+    }
+}
+
+/// Collects the indices of the bases that take the generics, which are themselves classes with
+/// generic bases.
+pub fn collect_family_bases(class: &ItemClass, generic_bases: &[GenericBase]) -> Vec<usize> {
+    class
+        .bases
+        .paths()
+        .enumerate()
+        .filter(|(_, base_path)| {
+            let PathArguments::AngleBracketed(args) = &last_segment(base_path).arguments else {
+                return false;
+            };
+            args.args.iter().any(|arg| {
+                generic_bases.iter().any(|generic_base| {
+                    matches!(arg, GenericArgument::Type(Type::Path(ty)) if ty.path.is_ident(&generic_base.base))
+                })
+            })
+        })
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Generates the invocation of the helper macro of a base with generic bases, which passes on how
+/// the base names its instantiations.
+pub fn gen_family_callback(def: &CppDef, base_path: &Path) -> File {
+    let macro_path = make_base_macro_path(base_path);
+    let selector = Helper::Family.selector();
+    let output = quote! {
+        #macro_path! {
+            #selector
+            [::vtable_gen::cpp_class]
+            #def
+        }
+    };
+    syn::parse(output.into()).expect("failed to generate family callback")
+}
+
+/// Instantiates a definition with generic bases. The class, and its bases that take the generics,
+/// are renamed after the bases, and the generics are replaced everywhere else, including the
+/// constructor impl. The bases are named by their own templates, in the order of
+/// `collect_family_bases`.
+pub fn instantiate(
+    def: &CppDef,
+    generic_bases: &[GenericBase],
+    template: &str,
+    base_templates: &[String],
+) -> CppDef {
+    let mut def = def.clone();
+    let family_bases = collect_family_bases(&def.class, generic_bases);
+    let virtuals_alias = make_virtuals_alias(&def.class, generic_bases);
+    for generic_base in generic_bases {
+        let base_names = collect_base_names(&def.class);
+
+        // rename the definition, and the bases by their own templates
+        let base_templates =
+            family_bases
+                .iter()
+                .zip(base_templates)
+                .filter_map(|(&idx, template)| {
+                    let base_path = def.class.bases.path(idx)?;
+                    Some((extract_ident(base_path).clone(), template.as_str()))
+                });
+        let mut replacer = GenericBaseReplacer {
+            generic_base,
+            templates: iter::once((def.class.ident.clone(), template))
+                .chain(base_templates)
+                .collect(),
+            members: HashMap::new(),
+        };
+        def.class.ident = GenericBaseName::make_ident(
+            template,
+            &def.class.ident,
+            extract_ident(&generic_base.repl),
+        );
+
+        // remove the generic
+        def.class.generics.params = remove_generic(&def.class.generics.params, &generic_base.base);
+
+        // replace the generic in the bases and the body
+        for (base, _) in &mut def.class.bases.bases {
+            replacer.visit_path_mut(base);
+        }
+        for field in def.class.body.fields.iter_mut() {
+            replacer.visit_type_mut(&mut field.ty);
+        }
+        for virt in def.class.body.virtuals.iter_mut() {
+            replacer.visit_signature_mut(&mut virt.sig);
+        }
+
+        // the constructors initialize the renamed bases, through their renamed constructors
+        replacer.members = base_names
+            .into_iter()
+            .zip(collect_base_names(&def.class))
+            .collect();
+        for imp in &mut def.impls {
+            imp.generics.params = remove_generic(&imp.generics.params, &generic_base.base);
+            replacer.visit_item_impl_mut(imp);
+        }
+    }
+
+    // the instantiation's virtuals can be implemented through the family
+    def.class
+        .attrs
+        .push(parse_quote!(#[virtuals_alias(#virtuals_alias)]));
+    def
+}
+
+/// Makes the path of the family's virtuals trait, instantiated with the generic bases.
+fn make_virtuals_alias(class: &ItemClass, generic_bases: &[GenericBase]) -> Path {
+    let virtuals_ident = make_virtuals(&class.ident);
+    let mut generic_args = class.vtable_generic_args();
+    for arg in generic_args.args.iter_mut() {
+        let GenericArgument::Type(Type::Path(ty)) = arg else {
+            continue;
+        };
+        if let Some(generic_base) = generic_bases
+            .iter()
+            .find(|generic_base| ty.path.is_ident(&generic_base.base))
+        {
+            ty.path = generic_base.repl.clone();
+        }
+    }
+    parse_quote!(#virtuals_ident #generic_args)
+}
+
+/// Replaces a generic base with its replacement. Classes that take the generic are renamed after
+/// the replacement, as are the fields of the renamed bases.
+struct GenericBaseReplacer<'a> {
+    generic_base: &'a GenericBase,
+    /// The name templates of the classes that are instantiated along with the class.
+    templates: HashMap<Ident, &'a str>,
+    /// The new names of the base fields.
+    members: HashMap<Ident, Ident>,
+}
+
+impl VisitMut for GenericBaseReplacer<'_> {
+    fn visit_path_mut(&mut self, path: &mut Path) {
+        // the generic itself
+        if path.segments.first().map(|segment| &segment.ident) == Some(&self.generic_base.base)
+            && path.segments[0].arguments.is_empty()
+        {
+            let mut repl = self.generic_base.repl.clone();
+            repl.segments.extend(path.segments.iter().skip(1).cloned());
+            *path = repl;
+        }
+
+        // classes that take the generic
+        for segment in path.segments.iter_mut() {
+            let Some(template) = self.templates.get(&segment.ident).copied() else {
+                continue;
+            };
+
+            if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                let len = args.args.len();
+                args.args = remove_punctuated(
+                    &args.args,
+                    |arg| !matches!(arg, GenericArgument::Type(Type::Path(ty)) if ty.path.is_ident(&self.generic_base.base)),
+                );
+                if args.args.len() != len {
+                    segment.ident = GenericBaseName::make_ident(
+                        template,
+                        &segment.ident,
+                        extract_ident(&self.generic_base.repl),
+                    );
+                }
+                if args.args.is_empty() {
+                    segment.arguments = PathArguments::None;
+                }
+            }
+        }
+
+        visit_mut::visit_path_mut(self, path);
+    }
+
+    fn visit_member_mut(&mut self, member: &mut Member) {
+        if let Member::Named(ident) = member {
+            if let Some(name) = self.members.get(ident) {
+                *ident = name.clone();
+            }
+        }
+    }
+}
+
+/// Collects the names of the fields of the bases of a class.
+fn collect_base_names(class: &ItemClass) -> Vec<Ident> {
+    class
+        .bases
+        .paths()
+        .map(|base_path| make_base_name(extract_ident(base_path)))
+        .collect()
+}
+
+/// Removes a generic type parameter.
+fn remove_generic(
+    params: &Punctuated<GenericParam, Token![,]>,
+    ident: &Ident,
+) -> Punctuated<GenericParam, Token![,]> {
+    remove_punctuated(params, |param| match param {
+        GenericParam::Type(ty) => &ty.ident != ident,
+        _ => true,
+    })
+}
+
+/// Make the identifier of the trait that maps the generic bases of a class to its instantiations.
+pub fn make_family_ident(ident: &Ident) -> Ident {
+    format_ident!("{ident}Family")
+}
+
+/// Generates the family trait of a class with generic bases, along with aliases that name its
+/// instantiations, their VTables and their virtuals through the generics of the class, as in
+/// `Foo<FooImpl>`. The class's helper macro passes on the name template to derived classes.
+pub fn gen_family(
+    class: &ItemClass,
+    base_collections: &[Vec<GenericBase>],
+    template: &str,
+) -> File {
+    let vis = &class.vis;
+    let ident = &class.ident;
+    let vtable_ident = make_vtable_ident(ident);
+    let family_ident = make_family_ident(ident);
+
+    // every instantiation must replace the same generics to share the family
+    let bases = base_collections
+        .iter()
+        .map(|generic_bases| {
+            collect_family_key(class, generic_bases)
+                .into_iter()
+                .map(|generic_base| generic_base.base.clone())
+                .collect_vec()
+        })
+        .all_equal_value()
+        .unwrap_or_else(|_| panic!("each instantiation of {ident} must replace the same generics"));
+    let key = make_key(&bases);
+
+    // the generics that aren't replaced parameterize the family
+    let params = class
+        .generics
+        .params
+        .iter()
+        .filter(|param| !matches!(param, GenericParam::Type(ty) if bases.contains(&ty.ident)))
+        .collect_vec();
+    let family_params = make_generics(params.iter().map(|param| make_param(param)));
+    let family_args = make_generics(params.iter().map(|param| make_arg(param, None)));
+    let vtable_family_args = make_generics(
+        params
+            .iter()
+            .map(|param| make_arg(param, Some(&parse_quote!('static)))),
+    );
+
+    // the VTables are shared by instances of any lifetime
+    let alias_params = make_generics(class.generics.params.iter().map(make_param));
+    let vtable_alias_params = make_generics(
+        class
+            .generics
+            .params
+            .iter()
+            .filter(|param| !matches!(param, GenericParam::Lifetime(_)))
+            .map(make_param),
+    );
+
+    let virtuals_alias = class
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("gen_vtable"))
+        .then(|| gen_virtuals_alias(class, &key, &family_ident, &params));
+
+    // derived classes name their bases' instantiations through the template
+    let selector = Helper::Family.selector();
+    let helper_macro = helper_macro::gen_helper_macro(
+        class,
+        &[quote! {
+            // callback: The path of `cpp_class`, passed in to keep the caller's hygiene.
+            // class: The definition of the derived class.
+            (#selector [$($callback:tt)*] $($class:tt)*) => {
+                $($callback)*! {
+                    #[base_generic_base_name = #template]
+                    $($class)*
+                }
+            };
+        }],
+    );
+
+    syn::parse(
+        quote! {
+            /// Maps the generic bases of the class to its instantiations.
+            #vis trait #family_ident #family_params {
+                /// The class instantiated with the bases.
+                type Class;
+                /// The VTable of the instantiation.
+                type VTable;
+            }
+
+            /// The class, instantiated with its generic bases.
+            #vis type #ident #alias_params = <#key as #family_ident #family_args>::Class;
+            /// The VTable of the class, instantiated with its generic bases.
+            #vis type #vtable_ident #vtable_alias_params =
+                <#key as #family_ident #vtable_family_args>::VTable;
+            #virtuals_alias
+
+            #helper_macro
+        }
+        .into(),
+    )
+    .expect("failed to generate generic base family")
+}
+
+/// Generates the virtuals trait of the family. Implementing it for an implementor implements the
+/// virtuals of the instantiation, which forward to it, so its functions use the Rust ABI.
+fn gen_virtuals_alias(
+    class: &ItemClass,
+    key: &TokenStream,
+    family_ident: &Ident,
+    params: &[&GenericParam],
+) -> ItemTrait {
+    let vis = &class.vis;
+    let virtuals_ident = make_virtuals(&class.ident);
+
+    // the virtuals take the class by its alias
+    let mut class = class.clone();
+    let destructor = Destructor::extract(&mut class, &[]);
+    standardize_virtuals(&mut class, &TokenStream::new(), "C", false);
+    let functions = trt::collect_functions(
+        &class,
+        true,
+        destructor.as_ref().and_then(Destructor::virtual_ident),
+    );
+
+    // the alias is only well-formed for the generic bases of an instantiation of any lifetime
+    let lifetimes = class.lifetimes();
+    let bound_lifetimes = (!lifetimes.is_empty()).then(|| quote!(for<#(#lifetimes),*>));
+    let family_args = make_generics(params.iter().map(|param| make_arg(param, None)));
+    let mut generics = class.vtable_generics();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(#bound_lifetimes #key: #family_ident #family_args));
+    let where_clause = &generics.where_clause;
+
+    parse_quote! {
+        /// The virtuals of the class, instantiated with its generic bases.
+        #vis trait #virtuals_ident #generics #where_clause {
+            #(#functions)*
+        }
+    }
+}
+
+/// Implements the family trait of a class for the generic bases of one of its instantiations.
+pub fn gen_family_impl(
+    class: &ItemClass,
+    instance: &ItemClass,
+    generic_bases: &[GenericBase],
+) -> ItemImpl {
+    let family_ident = make_family_ident(&class.ident);
+    let key = make_key(
+        &collect_family_key(class, generic_bases)
+            .into_iter()
+            .map(|generic_base| &generic_base.repl)
+            .collect_vec(),
+    );
+
+    // the instantiation is only well-formed with the bounds of its generics
+    let (impl_generics, _, where_clause) = instance.generics.split_for_impl();
+    let family_args = make_generics(
+        instance
+            .generics
+            .params
+            .iter()
+            .map(|param| make_arg(param, None)),
+    );
+
+    let ident = &instance.ident;
+    let generic_args = instance.generic_args();
+    let vtable_ident = make_vtable_ident(ident);
+    let vtable_generic_args = instance.vtable_generic_args();
+    parse_quote! {
+        impl #impl_generics #family_ident #family_args for #key #where_clause {
+            type Class = #ident #generic_args;
+            type VTable = #vtable_ident #vtable_generic_args;
+        }
+    }
+}
+
+/// Collects the generic bases that key the family, in the order of the generics of the class.
+fn collect_family_key<'a>(
+    class: &ItemClass,
+    generic_bases: &'a [GenericBase],
+) -> Vec<&'a GenericBase> {
+    class
+        .generics
+        .type_params()
+        .filter_map(|ty| {
+            generic_bases
+                .iter()
+                .find(|generic_base| generic_base.base == ty.ident)
+        })
+        .collect()
+}
+
+/// Makes the type that keys the family. Multiple generic bases are keyed by a tuple.
+fn make_key<T: ToTokens>(bases: &[T]) -> TokenStream {
+    match bases {
+        [base] => quote!(#base),
+        bases => quote!((#(#bases),*)),
+    }
+}
+
+/// Makes a generic parameter without its bounds or default.
+fn make_param(param: &GenericParam) -> TokenStream {
+    match param {
+        GenericParam::Lifetime(lt) => lt.lifetime.to_token_stream(),
+        GenericParam::Type(ty) => ty.ident.to_token_stream(),
+        GenericParam::Const(ct) => {
+            let ident = &ct.ident;
+            let ty = &ct.ty;
+            quote!(const #ident: #ty)
+        }
+    }
+}
+
+/// Makes the argument that passes on a generic parameter, with lifetimes optionally replaced.
+fn make_arg(param: &GenericParam, lifetime: Option<&Lifetime>) -> TokenStream {
+    match param {
+        GenericParam::Lifetime(lt) => lifetime.unwrap_or(&lt.lifetime).to_token_stream(),
+        GenericParam::Type(ty) => ty.ident.to_token_stream(),
+        GenericParam::Const(ct) => ct.ident.to_token_stream(),
+    }
+}
+
+/// Wraps generic parameters or arguments in angle brackets, if there are any.
+fn make_generics(generics: impl Iterator<Item = TokenStream>) -> Option<TokenStream> {
+    let generics = generics.collect_vec();
+    (!generics.is_empty()).then(|| quote!(<#(#generics),*>))
+}
@@ -0,0 +1,71 @@
+use proc_macro2::Ident;
+use quote::format_ident;
+use syn::{Attribute, Expr, ExprLit, Lit};
+
+use crate::class::extractor::AttributeExtractor;
+use crate::parse::ItemClass;
+
+/// The template that names the instantiations of a class with generic bases. `{class}` is replaced
+/// with the name of the class, and `{base}` with the name of the base it is instantiated with.
+pub struct GenericBaseName;
+
+impl GenericBaseName {
+    /// The template used when the class doesn't specify one.
+    pub const DEFAULT: &'static str = "{class}_{base}";
+
+    /// Names the instantiation of `class` with `base`.
+    pub fn make_ident(template: &str, class: &Ident, base: &Ident) -> Ident {
+        format_ident!(
+            "{}",
+            template
+                .replace("{class}", &class.to_string())
+                .replace("{base}", &base.to_string())
+        )
+    }
+}
+
+impl AttributeExtractor for GenericBaseName {
+    type Output = String;
+
+    fn attr() -> &'static str {
+        "generic_base_name"
+    }
+
+    fn parse_attr(attr: Attribute) -> syn::Result<Self::Output> {
+        match &attr.meta.require_name_value()?.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(template),
+                ..
+            }) => Ok(template.value()),
+            value => Err(syn::Error::new_spanned(value, "expected a string template")),
+        }
+    }
+}
+
+/// The name template of a base with generic bases, which the base's helper macro passes on so
+/// that its instantiations are named as the base names them.
+pub struct BaseGenericBaseName;
+
+impl AttributeExtractor for BaseGenericBaseName {
+    type Output = String;
+
+    fn attr() -> &'static str {
+        "base_generic_base_name"
+    }
+
+    fn parse_attr(attr: Attribute) -> syn::Result<Self::Output> {
+        GenericBaseName::parse_attr(attr)
+    }
+}
+
+/// Extracts the name templates of the bases with generic bases, in the order of the bases.
+pub fn extract_base_templates(class: &mut ItemClass) -> Vec<String> {
+    let mut templates = Vec::new();
+    while let Some(template) = BaseGenericBaseName::extract(class) {
+        templates.push(template);
+    }
+
+    // each helper macro prepends its attribute, so the last base comes first
+    templates.reverse();
+    templates
+}
@@ -0,0 +1,91 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::File;
+
+use crate::parse::ItemClass;
+use crate::util::replace_crate_paths;
+
+/// The helper macros of a class, which derived classes and implementors expand. Each is a set of
+/// arms of one macro named after the class, so the helpers are found wherever the class is, even
+/// when the class is imported or lives in another crate.
+#[derive(Debug, Clone, Copy)]
+pub enum Helper {
+    /// Builds the class's VTable for an implementor.
+    VTable,
+    /// Implements the class's virtuals with `unimplemented!()`.
+    Unimpl,
+    /// Forwards the class's virtuals from an implementor.
+    Bridge,
+    /// Passes on what derived classes inherit.
+    Info,
+    /// Passes on how the instantiations of a class with generic bases are named.
+    Family,
+}
+
+impl Helper {
+    /// Returns the tokens that lead each of the helper's arms and invocations.
+    pub fn selector(self) -> TokenStream {
+        let ident = match self {
+            Self::VTable => format_ident!("vtable"),
+            Self::Unimpl => format_ident!("unimpl"),
+            Self::Bridge => format_ident!("bridge"),
+            Self::Info => format_ident!("info"),
+            Self::Family => format_ident!("family"),
+        };
+        quote!(@#ident)
+    }
+}
+
+/// Generates the macro of the class out of the arms of its helpers. Exported macros live at the
+/// crate root, so it's exported under a hidden name that is unique to the class's definition and
+/// re-exported next to the class.
+pub fn gen_helper_macro(class: &ItemClass, arms: &[TokenStream]) -> File {
+    let ident = &class.ident;
+    let arms = arms.iter().cloned().map(replace_crate_paths).collect::<Vec<_>>();
+    let exported_ident = make_exported_ident(class, &arms);
+
+    let output = quote! {
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! #exported_ident {
+            #(#arms)*
+        }
+
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        pub use #exported_ident as #ident;
+    };
+    syn::parse(output.into()).expect("failed to generate helper macro")
+}
+
+/// Makes the name the class's macro is exported under out of a hash of the class and its helpers,
+/// so the name is the same in every build. Identical classes in different modules are told apart
+/// by where their names are written, which the debug output of the span holds on every compiler,
+/// unlike its line and column.
+fn make_exported_ident(class: &ItemClass, arms: &[TokenStream]) -> Ident {
+    let ident = &class.ident;
+    let mut hash = Fnv1a::default();
+    hash.write(&format!("{:?}", ident.span()));
+    hash.write(&class.to_token_stream().to_string());
+    for arm in arms {
+        hash.write(&arm.to_string());
+    }
+    format_ident!("__vtable_gen_{ident}_{:016x}", hash.0)
+}
+
+/// The 64-bit FNV-1a hash, which, unlike the hashers of `std`, is the same in every release.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, text: &str) {
+        for byte in text.bytes() {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
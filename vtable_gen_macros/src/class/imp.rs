@@ -0,0 +1,765 @@
+use std::collections::HashMap;
+use std::iter;
+use std::mem;
+
+use itertools::Itertools;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    Attribute, Block, Expr, ExprCall, ExprParen, ExprPath, ExprStruct, ExprTry, ExprUnary, FnArg,
+    ImplItem, ImplItemFn, ItemImpl, Member, parse_quote, Path, PathSegment, ReturnType, Stmt, Type,
+    UnOp, WhereClause,
+};
+use syn::visit_mut::{self, VisitMut};
+
+use crate::class::{make_base_name, placement};
+use crate::class::pinned::Pinned;
+use crate::class::vtable::{make_vtable_accessor, make_vtable_ident};
+use crate::parse::{CppDef, ItemClass};
+use crate::util::{collect_secondary_bases, extract_ident, make_base_vtable_ty, make_hygienic_ident};
+
+/// The constructors of a class.
+#[derive(Clone, Default)]
+pub struct Constructors {
+    /// The constructors, which take the vtables.
+    pub idents: Vec<Ident>,
+    /// The constructors that can also construct the class in place.
+    pub placeable: Vec<Ident>,
+    /// The constructors of each base, in the order of the bases.
+    pub bases: Vec<Constructors>,
+}
+
+/// Generates hooked versions of all implemented methods that construct instances. Other methods
+/// are passed through. Returns the constructors of the class along with the impls.
+pub fn gen_hooks(
+    def: &CppDef,
+    additional_bases: &HashMap<Path, Vec<Path>>,
+    base_constructors: Vec<Constructors>,
+    pinned: bool,
+) -> (Vec<ItemImpl>, Constructors) {
+    let mut impls = def.impls.clone();
+    let ident = &def.class.ident;
+
+    // make sure the impls are for us
+    for imp in &impls {
+        let Type::Path(ty) = &*imp.self_ty else {
+            panic!("implementation of a non-type found")
+        };
+        assert_eq!(
+            extract_ident(&ty.path),
+            ident,
+            "only implementations of the class type are allowed"
+        );
+    }
+
+    // generate new implementations (and pass through old ones, of course)
+    let constructors = collect_constructors(
+        &def.class,
+        &mut impls,
+        &base_constructors,
+        additional_bases,
+    );
+    let constructors = Constructors {
+        placeable: collect_placeable(&def.class, &impls, &constructors),
+        idents: constructors,
+        bases: base_constructors,
+    };
+    for imp in &mut impls {
+        gen_impl_items(&def.class, imp, &constructors, additional_bases, pinned);
+    }
+
+    (impls, constructors)
+}
+
+/// Collects the constructors of the inherent impls: the functions marked with `#[ctor]`, and the
+/// associated functions that instantiate the class, directly or through another constructor.
+fn collect_constructors(
+    class: &ItemClass,
+    impls: &mut [ItemImpl],
+    base_constructors: &[Constructors],
+    additional_bases: &HashMap<Path, Vec<Path>>,
+) -> Vec<Ident> {
+    let mut marked = Vec::new();
+    let mut candidates = Vec::new();
+    for imp in impls {
+        let is_inherent = imp.trait_.is_none();
+        for item in &mut imp.items {
+            let ImplItem::Fn(func) = item else {
+                continue;
+            };
+            if take_ctor_attr(&mut func.attrs) {
+                assert!(is_inherent, "constructors must be in inherent impls");
+                marked.push(func.sig.ident.clone());
+            } else if is_inherent && func.sig.receiver().is_none() {
+                candidates.push(func.clone());
+            }
+        }
+    }
+
+    // drop the candidates that turn out not to instantiate until none are left to drop
+    loop {
+        let constructors = marked
+            .iter()
+            .chain(candidates.iter().map(|func| &func.sig.ident))
+            .cloned()
+            .collect_vec();
+        let len = candidates.len();
+        candidates.retain(|func| {
+            hook_block(
+                class,
+                &mut func.block.clone(),
+                &constructors,
+                base_constructors,
+                additional_bases,
+                &func.sig.ident,
+            )
+        });
+
+        if candidates.len() == len {
+            return constructors;
+        }
+    }
+}
+
+/// Collects the constructors that return the class itself, which can construct it in place.
+fn collect_placeable(class: &ItemClass, impls: &[ItemImpl], constructors: &[Ident]) -> Vec<Ident> {
+    impls
+        .iter()
+        .filter(|imp| imp.trait_.is_none())
+        .flat_map(|imp| &imp.items)
+        .filter_map(|item| match item {
+            ImplItem::Fn(func) if is_placeable(class, func) => Some(&func.sig.ident),
+            _ => None,
+        })
+        .filter(|ident| constructors.contains(ident))
+        .cloned()
+        .collect()
+}
+
+/// Returns true if a constructor can construct the class in place, which requires it to return the
+/// class itself.
+fn is_placeable(class: &ItemClass, func: &ImplItemFn) -> bool {
+    func.sig.receiver().is_none() && returns_class(class, func)
+}
+
+/// Removes the `#[ctor]` marker from the attributes, returning whether it was there.
+fn take_ctor_attr(attrs: &mut Vec<Attribute>) -> bool {
+    let len = attrs.len();
+    attrs.retain(|attr| !attr.path().is_ident("ctor"));
+    attrs.len() != len
+}
+
+/// Processes a constructor, generating 2 functions: one that takes the vtables and one that
+/// passes the class's own. Calls to the other `constructors` are forwarded the vtables too.
+/// Constructors returning the class also get a pair that constructs it in place. If the class is
+/// `pinned`, they pin it instead.
+pub fn hook_fn(
+    class: &ItemClass,
+    mut func: ImplItemFn,
+    constructors: &Constructors,
+    additional_bases: &HashMap<Path, Vec<Path>>,
+    pinned: bool,
+) -> Vec<ImplItemFn> {
+    // generate the stub functions
+    let placeable = is_placeable(class, &func);
+    let mut stub_fns = if pinned && placeable {
+        gen_pinned_stubs(class, &func, additional_bases).to_vec()
+    } else {
+        vec![gen_stub(class, &func, additional_bases)]
+    };
+    if placeable {
+        stub_fns.extend(placement::gen_construct_at(
+            class,
+            &func,
+            constructors,
+            additional_bases,
+        ));
+    }
+
+    // add the vtable input parameters
+    func.sig
+        .inputs
+        .extend(make_vtable_params(class, additional_bases));
+
+    // inject the vtables into every instantiation of the class in the body
+    let fn_ident = func.sig.ident.clone();
+    if !hook_block(
+        class,
+        &mut func.block,
+        &constructors.idents,
+        &constructors.bases,
+        additional_bases,
+        &fn_ident,
+    ) {
+        panic!("only impls that instantiate the target are allowed")
+    }
+
+    // rename this function and create another dummy
+    func.sig.ident = make_ctor_call(&func.sig.ident);
+
+    iter::once(func).chain(stub_fns).collect()
+}
+
+/// Injects the vtables into every instantiation of the class in the block of `fn_ident`, and
+/// forwards them to the `constructors` it calls, and the `base_constructors` its bases are
+/// initialized with. Returns true if there were any.
+pub fn hook_block(
+    class: &ItemClass,
+    block: &mut Block,
+    constructors: &[Ident],
+    base_constructors: &[Constructors],
+    additional_bases: &HashMap<Path, Vec<Path>>,
+    fn_ident: &Ident,
+) -> bool {
+    let mut hook = InstantiationHook {
+        class,
+        constructors,
+        base_constructors,
+        additional_bases,
+        fn_ident: fn_ident.clone(),
+        hooked: false,
+    };
+    hook.visit_block_mut(block);
+    hook.hooked
+}
+
+/// Injects the vtables into each instantiation of the class, wherever it is in the function.
+struct InstantiationHook<'a> {
+    class: &'a ItemClass,
+    constructors: &'a [Ident],
+    base_constructors: &'a [Constructors],
+    additional_bases: &'a HashMap<Path, Vec<Path>>,
+    fn_ident: Ident,
+    hooked: bool,
+}
+
+impl VisitMut for InstantiationHook<'_> {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        visit_mut::visit_expr_mut(self, expr);
+        if let Expr::Call(call) = expr {
+            self.hook_delegation(call);
+            return;
+        }
+        let Expr::Struct(stct) = expr else {
+            return;
+        };
+        if !is_instantiation(self.class, stct) {
+            return;
+        }
+
+        // bases left to the struct update have their vtables replaced before they're moved out
+        let rest = take_rest_bases(self.class, stct);
+        self.hook_instantiation(stct);
+        if let Some((rest, rest_bases)) = rest {
+            let rebinds = rest_bases.into_iter().map(|idx| -> Stmt {
+                let base_ty = self.class.bases.path(idx).unwrap();
+                let base_ident = make_base_name(extract_ident(base_ty));
+                let vtable_args = make_base_vtable_args(idx, base_ty, self.additional_bases);
+                parse_quote!(<#base_ty>::_rebind_vtable(&mut __rest.#base_ident, #(#vtable_args),*);)
+            });
+            *expr = parse_quote! {{
+                let mut __rest: Self = #rest;
+                #(#rebinds)*
+                #stct
+            }};
+        }
+
+        self.hooked = true;
+    }
+}
+
+impl InstantiationHook<'_> {
+    /// Forwards the vtables to a call of another constructor of the class, like `Self::new(..)`.
+    fn hook_delegation(&mut self, call: &mut ExprCall) {
+        let Some(fn_segment) = as_delegation(self.class, call, self.constructors) else {
+            return;
+        };
+
+        fn_segment.ident = make_ctor_call(&fn_segment.ident);
+        call.args
+            .extend(make_vtable_args(self.class, self.additional_bases));
+        self.hooked = true;
+    }
+
+    /// Injects the vtables into the initializers of the bases, or the class itself.
+    fn hook_instantiation(&self, expr: &mut ExprStruct) {
+        let fn_ident = &self.fn_ident;
+        for (idx, base_ty) in self.class.bases.paths().enumerate() {
+            let base_ident = make_base_name(extract_ident(base_ty));
+            let field_setter = expr.fields.iter_mut().find(|field| match &field.member {
+                Member::Named(ident) => ident == &base_ident,
+                _ => false,
+            });
+            let Some(field_setter) = field_setter else {
+                // bases left to the struct update are rebound in it
+                assert!(
+                    expr.rest.is_some(),
+                    "{base_ident} must be initialized in {fn_ident}"
+                );
+                continue;
+            };
+
+            // constructors of the base take the vtables themselves. anything else producing the
+            // base has its vtables replaced
+            let vtable_args = make_base_vtable_args(idx, base_ty, self.additional_bases);
+            if let Some(call) = as_base_constructor(
+                peel_base_value(&mut field_setter.expr),
+                base_ty,
+                &self.base_constructors[idx].idents,
+            ) {
+                let Expr::Path(fn_path) = &mut *call.func else {
+                    unreachable!()
+                };
+                let fn_segment = fn_path.path.segments.last_mut().unwrap();
+                fn_segment.ident = make_ctor_call(&fn_segment.ident);
+                call.args.extend(vtable_args);
+            } else {
+                field_setter.expr = make_rebind(&field_setter.expr, base_ty, &vtable_args);
+            }
+        }
+
+        if self.class.bases.is_empty() {
+            expr.fields
+                .insert(0, parse_quote!(vfptr: ::vtable_gen::VfPtr::new(vfptr)))
+        }
+
+        // pinned classes are kept from being `Unpin` by a marker the user doesn't initialize
+        let pinned_ident = Pinned::make_field_ident();
+        let has_pinned = expr
+            .fields
+            .iter()
+            .any(|field| matches!(&field.member, Member::Named(ident) if ident == &pinned_ident));
+        if Pinned::has_field(self.class) && !has_pinned && expr.rest.is_none() {
+            expr.fields
+                .push(parse_quote!(#pinned_ident: ::core::marker::PhantomPinned));
+        }
+    }
+}
+
+/// Returns the segment naming the constructor if the call delegates to one of the `constructors`
+/// of the class, like `Self::new(..)`.
+pub fn as_delegation<'a>(
+    class: &ItemClass,
+    call: &'a mut ExprCall,
+    constructors: &[Ident],
+) -> Option<&'a mut PathSegment> {
+    let Expr::Path(ExprPath {
+        qself: None, path, ..
+    }) = &mut *call.func
+    else {
+        return None;
+    };
+    let [ty_segment, fn_segment] = &mut path.segments.iter_mut().collect_vec()[..] else {
+        return None;
+    };
+    if (ty_segment.ident != "Self" && ty_segment.ident != class.ident)
+        || !constructors.contains(&fn_segment.ident)
+    {
+        return None;
+    }
+
+    let Expr::Path(fn_path) = &mut *call.func else {
+        unreachable!()
+    };
+    fn_path.path.segments.last_mut()
+}
+
+/// Makes an expression that replaces the vtables of the base produced by `value`.
+pub fn make_rebind(value: &Expr, base_ty: &Path, vtable_args: &[Expr]) -> Expr {
+    parse_quote!({
+        let mut __base: #base_ty = #value;
+        <#base_ty>::_rebind_vtable(&mut __base, #(#vtable_args),*);
+        __base
+    })
+}
+
+/// Peels the operators that unwrap the result of a constructor, like `A::try_new(..)?` or
+/// `*A::boxed(..)`.
+fn peel_base_value(mut expr: &mut Expr) -> &mut Expr {
+    loop {
+        expr = match expr {
+            Expr::Try(ExprTry { expr, .. })
+            | Expr::Paren(ExprParen { expr, .. })
+            | Expr::Unary(ExprUnary {
+                op: UnOp::Deref(_),
+                expr,
+                ..
+            }) => expr,
+            expr => return expr,
+        }
+    }
+}
+
+/// Returns the call if the expression calls one of the `constructors` of the base by its path, like
+/// `A::new(..)` or `<A>::new(..)`.
+pub fn as_base_constructor<'a>(
+    expr: &'a mut Expr,
+    base_ty: &Path,
+    constructors: &[Ident],
+) -> Option<&'a mut ExprCall> {
+    let Expr::Call(call) = expr else {
+        return None;
+    };
+    let Expr::Path(fn_path) = &*call.func else {
+        return None;
+    };
+    let segments = &fn_path.path.segments;
+    let base_ident = extract_ident(base_ty);
+    let is_constructor = match &fn_path.qself {
+        Some(qself) => {
+            qself.position == 0
+                && segments.len() == 1
+                && matches!(&*qself.ty, Type::Path(ty) if extract_ident(&ty.path) == base_ident)
+        }
+        None => segments.len() >= 2 && segments[segments.len() - 2].ident == *base_ident,
+    };
+    let is_exported = segments
+        .last()
+        .is_some_and(|segment| constructors.contains(&segment.ident));
+    (is_constructor && is_exported).then_some(call)
+}
+
+/// Takes the struct update out of an instantiation that leaves bases to it, replacing it with
+/// `__rest`. Returns the update, and the indices of the bases left to it.
+///
+/// Like any struct update, the bases are moved out of the update, which can't be done with classes
+/// that implement `Drop`. Those have to initialize their bases themselves.
+fn take_rest_bases(class: &ItemClass, expr: &mut ExprStruct) -> Option<(Expr, Vec<usize>)> {
+    let rest_bases = class
+        .bases
+        .paths()
+        .map(|base_ty| make_base_name(extract_ident(base_ty)))
+        .positions(|base_ident| {
+            !expr
+                .fields
+                .iter()
+                .any(|field| matches!(&field.member, Member::Named(ident) if ident == &base_ident))
+        })
+        .collect_vec();
+    if rest_bases.is_empty() {
+        return None;
+    }
+
+    let rest = expr.rest.replace(parse_quote!(__rest))?;
+    Some((*rest, rest_bases))
+}
+
+/// Makes the vtable parameters of a hooked constructor: the class's vtable, followed by the
+/// vtables of its secondary bases.
+pub fn make_vtable_params(
+    class: &ItemClass,
+    additional_bases: &HashMap<Path, Vec<Path>>,
+) -> Vec<FnArg> {
+    let generic_args = class.vtable_generic_args();
+    let vtable_ty = make_vtable_ident(&class.ident);
+    iter::once(parse_quote!(vfptr: &'static #vtable_ty #generic_args))
+        .chain(
+            collect_secondary_bases(class, additional_bases)
+                .into_iter()
+                .map(|base_ty| {
+                    let base_param = make_base_name(extract_ident(base_ty));
+                    let vtable_ty = make_base_vtable_ty(base_ty);
+                    parse_quote!(#base_param: &'static #vtable_ty)
+                }),
+        )
+        .collect()
+}
+
+/// Makes the vtable arguments that a constructor passes on to another constructor of the class.
+pub fn make_vtable_args(
+    class: &ItemClass,
+    additional_bases: &HashMap<Path, Vec<Path>>,
+) -> Vec<Expr> {
+    iter::once(parse_quote!(vfptr))
+        .chain(
+            collect_secondary_bases(class, additional_bases)
+                .into_iter()
+                .map(|base_ty| {
+                    let base_ident = make_base_name(extract_ident(base_ty));
+                    parse_quote!(#base_ident)
+                }),
+        )
+        .collect()
+}
+
+/// Makes the vtable arguments that a constructor of the base at `idx` is called with.
+pub fn make_base_vtable_args(
+    idx: usize,
+    base_ty: &Path,
+    additional_bases: &HashMap<Path, Vec<Path>>,
+) -> Vec<Expr> {
+    let base_ident = make_base_name(extract_ident(base_ty));
+
+    // the base vtable is contained within the parent vtable, and secondary vtables are separate
+    let vtable_arg = if idx == 0 {
+        parse_quote!(&vfptr.#base_ident)
+    } else {
+        parse_quote!(#base_ident)
+    };
+
+    // add additional params
+    iter::once(vtable_arg)
+        .chain(
+            additional_bases
+                .get(base_ty)
+                .into_iter()
+                .flatten()
+                .map(|additional_base_ty| {
+                    let base_ident = make_base_name(extract_ident(additional_base_ty));
+                    parse_quote!(#base_ident)
+                }),
+        )
+        .collect()
+}
+
+/// Generates the function that replaces the vtables of an instance with those of a class that
+/// derives from it, so that any expression producing the class can initialize a base.
+pub fn gen_rebind(class: &ItemClass, additional_bases: &HashMap<Path, Vec<Path>>) -> ItemImpl {
+    let ident = &class.ident;
+    let (impl_generics, _, where_clause) = class.generics.split_for_impl();
+    let generic_args = class.generic_args();
+    let vtable_params = make_vtable_params(class, additional_bases);
+
+    let rebinds = if class.bases.is_empty() {
+        vec![quote!(this.vfptr = ::vtable_gen::VfPtr::new(vfptr);)]
+    } else {
+        class
+            .bases
+            .paths()
+            .enumerate()
+            .map(|(idx, base_ty)| {
+                let base_ident = make_base_name(extract_ident(base_ty));
+                let vtable_args = make_base_vtable_args(idx, base_ty, additional_bases);
+                quote! {
+                    <#base_ty>::_rebind_vtable(&mut this.#base_ident, #(#vtable_args),*);
+                }
+            })
+            .collect()
+    };
+
+    parse_quote! {
+        impl #impl_generics #ident #generic_args #where_clause {
+            /// Replaces the vtables of an instance with those of a class that derives from it.
+            #[doc(hidden)]
+            pub const fn _rebind_vtable(this: &mut Self, #(#vtable_params),*) {
+                #(#rebinds)*
+            }
+        }
+    }
+}
+
+/// Binds the class's own vtables in a method that instantiates the class without being a
+/// constructor, and returns whether it did. Methods that don't are returned as they are.
+fn bind_vtables(
+    class: &ItemClass,
+    mut func: ImplItemFn,
+    constructors: &Constructors,
+    additional_bases: &HashMap<Path, Vec<Path>>,
+) -> (ImplItemFn, bool) {
+    let fn_ident = func.sig.ident.clone();
+    if !hook_block(
+        class,
+        &mut func.block,
+        &constructors.idents,
+        &constructors.bases,
+        additional_bases,
+        &fn_ident,
+    ) {
+        return (func, false);
+    }
+
+    let bindings = make_vtable_params(class, additional_bases)
+        .into_iter()
+        .zip(make_vtable_accessor_calls(class, additional_bases))
+        .map(|(param, accessor_call)| -> Stmt { parse_quote!(let #param = #accessor_call;) });
+    func.block.stmts.splice(0..0, bindings);
+    (func, true)
+}
+
+/// Makes the calls to the accessors of the class's own vtables, in the order of the vtable
+/// parameters.
+pub fn make_vtable_accessor_calls(
+    class: &ItemClass,
+    additional_bases: &HashMap<Path, Vec<Path>>,
+) -> Vec<Expr> {
+    let generic_args = class.generic_args_with_lifetime(&parse_quote!('_));
+    iter::once(&class.ident)
+        .chain(
+            collect_secondary_bases(class, additional_bases)
+                .into_iter()
+                .map(extract_ident),
+        )
+        .map(|base_ident| {
+            let accessor = make_vtable_accessor(&class.ident, base_ident, &generic_args);
+            parse_quote!(#accessor())
+        })
+        .collect()
+}
+
+/// Makes the where clause of a function that looks up the class's vtables, which bounds each type
+/// parameter of the class to be `'static`.
+pub fn make_static_where_clause(class: &ItemClass) -> Option<WhereClause> {
+    let predicates = class.static_predicates();
+    (!predicates.is_empty()).then(|| parse_quote!(where #(#predicates),*))
+}
+
+/// Make a call to a constructor with a VTable passthrough.
+pub fn make_ctor_call(ident: &Ident) -> Ident {
+    format_ident!("_{ident}_with_vtable")
+}
+
+/// Generates all implementation items. Looking up the class's vtables needs its type parameters to
+/// be `'static`, which the methods of trait impls can't require themselves, so their impl does.
+fn gen_impl_items(
+    class: &ItemClass,
+    imp: &mut ItemImpl,
+    constructors: &Constructors,
+    additional_bases: &HashMap<Path, Vec<Path>>,
+    pinned: bool,
+) {
+    let is_inherent = imp.trait_.is_none();
+    let mut binds_vtables = false;
+    imp.items = mem::take(&mut imp.items)
+        .into_iter()
+        .flat_map(|item| match item {
+            ImplItem::Fn(item_fn)
+                if is_inherent && constructors.idents.contains(&item_fn.sig.ident) =>
+            {
+                hook_fn(class, item_fn, constructors, additional_bases, pinned)
+                    .into_iter()
+                    .map(ImplItem::Fn)
+                    .collect()
+            }
+            ImplItem::Fn(item_fn) => {
+                let (mut item_fn, binds) =
+                    bind_vtables(class, item_fn, constructors, additional_bases);
+                if binds && is_inherent {
+                    item_fn
+                        .sig
+                        .generics
+                        .make_where_clause()
+                        .predicates
+                        .extend(class.static_predicates());
+                }
+                binds_vtables |= binds;
+                vec![ImplItem::Fn(item_fn)]
+            }
+            item => vec![item],
+        })
+        .collect();
+
+    if binds_vtables && !is_inherent {
+        imp.generics
+            .make_where_clause()
+            .predicates
+            .extend(class.static_predicates());
+    }
+}
+
+/// Generates a stub of a function that calls the original implementation with the current
+/// class's vtable.
+fn gen_stub(
+    class: &ItemClass,
+    func: &ImplItemFn,
+    additional_bases: &HashMap<Path, Vec<Path>>,
+) -> ImplItemFn {
+    // create a proxy function
+    let vis = &func.vis;
+    let abi = &func.sig.abi;
+    let unsafety = &func.sig.unsafety;
+    let ident = &func.sig.ident;
+    let args = &func.sig.inputs;
+    let output = &func.sig.output;
+    let arg_names = collect_arg_names(func);
+
+    let proxy_ident = make_ctor_call(ident);
+    let accessor_calls = make_vtable_accessor_calls(class, additional_bases);
+
+    // the vtables of generic classes are registered at runtime, so only the variant taking the
+    // vtables stays const
+    let constness = func
+        .sig
+        .constness
+        .filter(|_| class.vtable_generic_args().args.is_empty());
+
+    let where_clause = make_static_where_clause(class);
+    let output = quote! {
+        #vis #constness #unsafety #abi fn #ident(#args) #output #where_clause {
+            Self::#proxy_ident(#(#arg_names,)* #(#accessor_calls),*)
+        }
+    };
+    syn::parse(output.into()).expect("failed to generate stub")
+}
+
+/// Generates the stubs of a constructor of a pinned class: one that pins the instance in a box, and
+/// one that initializes it in place.
+fn gen_pinned_stubs(
+    class: &ItemClass,
+    func: &ImplItemFn,
+    additional_bases: &HashMap<Path, Vec<Path>>,
+) -> [ImplItemFn; 2] {
+    let vis = &func.vis;
+    let abi = &func.sig.abi;
+    let unsafety = &func.sig.unsafety;
+    let ident = &func.sig.ident;
+    let args = &func.sig.inputs;
+    let arg_names = collect_arg_names(func);
+
+    let proxy_ident = make_ctor_call(ident);
+    let in_place_ident = format_ident!("{ident}_in_place");
+    let construct_ident = placement::make_construct_at_ident(ident);
+    let accessor_calls = make_vtable_accessor_calls(class, additional_bases);
+    let slot = make_hygienic_ident("slot");
+    let where_clause = make_static_where_clause(class);
+
+    [
+        parse_quote! {
+            #vis #unsafety #abi fn #ident(#args) -> ::core::pin::Pin<::vtable_gen::Box<Self>>
+            #where_clause
+            {
+                ::vtable_gen::Box::pin(Self::#proxy_ident(#(#arg_names,)* #(#accessor_calls),*))
+            }
+        },
+        parse_quote! {
+            /// Initializes an instance in place, so that it never moves.
+            ///
+            /// # Safety
+            /// `slot` must be valid for writes and properly aligned.
+            #vis unsafe #abi fn #in_place_ident(#slot: *mut Self, #args) #where_clause {
+                Self::#construct_ident(#slot.cast(), #(#arg_names),*)
+            }
+        },
+    ]
+}
+
+/// Collects the names of the arguments of a function, as it would pass them on.
+pub fn collect_arg_names(func: &ImplItemFn) -> Vec<TokenStream> {
+    func.sig
+        .inputs
+        .iter()
+        .map(|arg| match arg {
+            FnArg::Receiver(_) => quote!(self),
+            FnArg::Typed(ty) => ty.pat.to_token_stream(),
+        })
+        .collect()
+}
+
+/// Returns true if the function returns the class itself.
+pub fn returns_class(class: &ItemClass, func: &ImplItemFn) -> bool {
+    let ReturnType::Type(_, ty) = &func.sig.output else {
+        return false;
+    };
+    matches!(&**ty, Type::Path(ty) if ty.path.is_ident("Self") || ty.path.is_ident(&class.ident))
+}
+
+/// Returns true if the expression instantiates the class.
+pub fn is_instantiation(class: &ItemClass, expr: &ExprStruct) -> bool {
+    expr.path == parse_quote!(Self)
+        || expr
+            .path
+            .segments
+            .last()
+            .expect("expected path segments")
+            .ident
+            == class.ident
+}
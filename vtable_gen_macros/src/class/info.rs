@@ -0,0 +1,116 @@
+use itertools::Itertools;
+use quote::quote;
+use syn::{File, ItemImpl, LitStr, parse_quote, Signature};
+
+use crate::class::make_base_name;
+use crate::class::vtable::{make_vtable_accessor, make_vtable_ident, sort_virtuals};
+use crate::parse::ItemClass;
+use crate::util::extract_ident;
+
+/// Generates the reflection metadata for the class.
+pub fn gen_class_info(class: &ItemClass, gen_vtable: bool) -> ItemImpl {
+    let ident = &class.ident;
+    let name = LitStr::new(&ident.to_string(), ident.span());
+    let static_generics = class.static_generics();
+    let (impl_generics, _, where_clause) = static_generics.split_for_impl();
+    let generic_args = class.generic_args();
+    let vtable_generic_args = class.vtable_generic_args();
+    let vtable_ident = make_vtable_ident(ident);
+
+    // collect the bases with their offsets
+    let bases = class
+        .bases
+        .paths()
+        .map(|base_path| {
+            let base_name = make_base_name(extract_ident(base_path));
+            quote! {
+                ::vtable_gen::BaseInfo {
+                    info: &<#base_path>::CLASS_INFO,
+                    offset: ::core::mem::offset_of!(Self, #base_name),
+                }
+            }
+        })
+        .collect_vec();
+
+    // collect the slots declared by the class
+    let slots = sort_virtuals(class)
+        .into_values()
+        .map(|virt| {
+            let slot_ident = &virt.sig.ident;
+            let slot_name = LitStr::new(&slot_ident.to_string(), slot_ident.span());
+            let abi = virt
+                .sig
+                .abi
+                .as_ref()
+                .and_then(|abi| abi.name.as_ref())
+                .map(LitStr::value)
+                .unwrap_or_else(|| "C".to_owned());
+            let signature = print_signature(&virt.sig);
+
+            quote! {
+                ::vtable_gen::SlotInfo {
+                    name: #slot_name,
+                    index: ::core::mem::offset_of!(#vtable_ident #vtable_generic_args, #slot_ident)
+                        / ::core::mem::size_of::<usize>(),
+                    abi: #abi,
+                    signature: #signature,
+                }
+            }
+        })
+        .collect_vec();
+
+    let vtable = if gen_vtable {
+        let accessor = extract_ident(&make_vtable_accessor(ident, ident, &generic_args)).clone();
+        quote!(::core::option::Option::Some(|| Self::#accessor() as *const _ as *const ()))
+    } else {
+        quote!(::core::option::Option::None)
+    };
+
+    syn::parse(
+        quote! {
+            impl #impl_generics #ident #generic_args #where_clause {
+                /// The reflection metadata of the class.
+                pub const CLASS_INFO: ::vtable_gen::ClassInfo = ::vtable_gen::ClassInfo {
+                    name: #name,
+                    size: ::core::mem::size_of::<Self>(),
+                    bases: &[#(#bases),*],
+                    slots: &[#(#slots),*],
+                    vtable: #vtable,
+                };
+
+                /// Returns the slots whose functions in the object's vtable differ from those of
+                /// the classes that declare them.
+                pub fn overridden_slots(&self) -> impl ::core::iter::Iterator<Item = &'static ::vtable_gen::SlotInfo> {
+                    // SAFETY: the object's primary vtable was installed for this class or a subclass
+                    unsafe {
+                        (&Self::CLASS_INFO).overridden_slots(self.vfptr.as_ptr() as *const ())
+                    }
+                }
+            }
+        }
+        .into(),
+    )
+    .expect("failed to generate class info")
+}
+
+/// Prints a signature as it would be written, on a single line.
+fn print_signature(sig: &Signature) -> String {
+    let file: File = parse_quote! {
+        trait __Signature {
+            #sig;
+        }
+    };
+
+    // long signatures are wrapped over several lines, so join them back
+    prettyplease::unparse(&file)
+        .lines()
+        .skip(1)
+        .take_while(|line| *line != "}")
+        .map(str::trim)
+        .join(" ")
+        .trim_end_matches(';')
+        .replace("( ", "(")
+        .replace(", )", ")")
+        .replace("< ", "<")
+        .replace(", >", ">")
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{Attribute, bracketed, File, LitBool, LitStr, Path, Token};
+use syn::parse::{Parse, ParseStream};
+
+use crate::class::extractor::AttributeExtractor;
+use crate::class::helper_macro::Helper;
+use crate::class::imp::Constructors;
+use crate::parse::{CppDef, ItemClass};
+use crate::util::{
+    last_segment, make_base_macro_path, make_base_module,
+    make_generic_matchers, make_macro_prefix, qualify_path, replace_crate_paths,
+    replace_generics_with_descriptors, strip_lifetimes,
+};
+
+/// What a class inherits from one of its bases, injected by the base's info macro.
+pub struct Inherited {
+    /// The default ABI of the base.
+    pub abi: String,
+    /// The secondary bases of the base, in terms of the class's generics.
+    pub bases: Vec<Path>,
+    /// Whether the base can be defaulted in const contexts.
+    pub const_default: bool,
+    /// Whether the base is pinned.
+    pub pinned: bool,
+    /// Whether the base has a virtual destructor.
+    pub destructor: bool,
+    /// The names of the virtuals that can be called on the base without a prefix.
+    pub virtuals: Vec<Ident>,
+    /// The constructors of the base, which take the vtables.
+    pub constructors: Constructors,
+}
+
+impl AttributeExtractor for Inherited {
+    type Output = Self;
+
+    fn attr() -> &'static str {
+        "inherited"
+    }
+
+    fn parse_attr(attr: Attribute) -> syn::Result<Self::Output> {
+        attr.parse_args()
+    }
+}
+
+impl Parse for Inherited {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut abi = None;
+        let mut bases = Vec::new();
+        let mut const_default = false;
+        let mut pinned = false;
+        let mut destructor = false;
+        let mut virtuals = Vec::new();
+        let mut constructors = Constructors::default();
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            if key == "abi" {
+                abi = Some(input.parse::<LitStr>()?.value());
+            } else if key == "bases" {
+                let contents;
+                bracketed!(contents in input);
+                bases = contents
+                    .parse_terminated(Path::parse, Token![,])?
+                    .into_iter()
+                    .collect();
+            } else if key == "const_default" {
+                const_default = input.parse::<LitBool>()?.value;
+            } else if key == "pinned" {
+                pinned = input.parse::<LitBool>()?.value;
+            } else if key == "destructor" {
+                destructor = input.parse::<LitBool>()?.value;
+            } else if key == "virtuals" {
+                virtuals = parse_idents(input)?;
+            } else if key == "constructors" {
+                constructors.idents = parse_idents(input)?;
+            } else if key == "placeable" {
+                constructors.placeable = parse_idents(input)?;
+            } else {
+                return Err(syn::Error::new(key.span(), "unknown inherited property"));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(Self {
+            abi: abi.ok_or_else(|| input.error("missing `abi`"))?,
+            bases,
+            const_default,
+            pinned,
+            destructor,
+            virtuals,
+            constructors,
+        })
+    }
+}
+
+/// Parses a bracketed list of identifiers.
+fn parse_idents(input: ParseStream) -> syn::Result<Vec<Ident>> {
+    let contents;
+    bracketed!(contents in input);
+    Ok(contents
+        .parse_terminated(Ident::parse, Token![,])?
+        .into_iter()
+        .collect())
+}
+
+/// Extracts what the class inherits from each of its bases, in the order of the bases.
+pub fn extract_inherited(class: &mut ItemClass) -> Vec<Inherited> {
+    let mut inherited = Vec::new();
+    while let Some(info) = Inherited::extract(class) {
+        inherited.push(info);
+    }
+
+    // each info macro prepends its attribute, so the last base comes first
+    inherited.reverse();
+    inherited
+}
+
+/// Collects the secondary bases inherited from each base, keyed by the base.
+pub fn collect_inherited_bases(
+    class: &ItemClass,
+    inherited: &[Inherited],
+) -> HashMap<Path, Vec<Path>> {
+    class
+        .bases
+        .paths()
+        .zip(inherited)
+        .filter(|(_, info)| !info.bases.is_empty())
+        .map(|(base_path, info)| (base_path.clone(), info.bases.clone()))
+        .collect()
+}
+
+/// Generates the helper macro arm that re-invokes `cpp_class!` on a derived class with what it
+/// inherits from the class. The secondary bases of `info` are those of the class.
+pub fn gen_info_macro(class: &ItemClass, info: &Inherited, prefix: &TokenStream) -> TokenStream {
+    let selector = Helper::Info.selector();
+    let abi = LitStr::new(&info.abi, Span::call_site());
+    let const_default = LitBool::new(info.const_default, Span::call_site());
+    let pinned = LitBool::new(info.pinned, Span::call_site());
+    let destructor = LitBool::new(info.destructor, Span::call_site());
+    let virtuals = &info.virtuals;
+    let constructors = &info.constructors.idents;
+    let placeable = &info.constructors.placeable;
+
+    // collect all generic args into descriptors
+    let def_generic_matchers = make_generic_matchers(class);
+
+    // the secondary bases, relative to the derived class. derived classes only name their
+    // VTables, which don't have lifetimes
+    let prefix = make_macro_prefix(prefix);
+    let bases = info
+        .bases
+        .iter()
+        .map(|base_path| qualify_path(&prefix, &strip_lifetimes(base_path)));
+    let bases = replace_crate_paths(replace_generics_with_descriptors(
+        class,
+        quote!(#(#bases),*),
+    ));
+
+    quote! {
+        // callback: The path of `cpp_class`, passed in to keep the caller's hygiene.
+        // module: The path of the module the class lives in, relative to the caller.
+        // gen_x: The definition generic at position `x`.
+        // class: The definition of the derived class.
+        (#selector [$($callback:tt)*] [$($module:tt)*] <#(#def_generic_matchers),*> $($class:tt)*) => {
+            $($callback)*! {
+                #[inherited(abi = #abi, bases = [#bases], const_default = #const_default, pinned = #pinned, destructor = #destructor, virtuals = [#(#virtuals),*], constructors = [#(#constructors),*], placeable = [#(#placeable),*])]
+                $($class)*
+            }
+        };
+    }
+}
+
+/// Defers the generation of the class to the info macro of the given base.
+pub fn gen_info_callback(def: &CppDef, base_path: &Path) -> File {
+    let macro_path = make_base_macro_path(base_path);
+    let selector = Helper::Info.selector();
+    let base_module = make_base_module(base_path);
+    let base_args = last_segment(&strip_lifetimes(base_path)).arguments.clone();
+    let base_args = if base_args.is_empty() {
+        quote!(<>)
+    } else {
+        quote!(#base_args)
+    };
+    let output = quote! {
+        #macro_path! {
+            #selector
+            [::vtable_gen::cpp_class]
+            [#base_module]
+            #base_args
+            #def
+        }
+    };
+    syn::parse(output.into()).expect("failed to generate info callback")
+}
@@ -0,0 +1,327 @@
+use convert_case::{Case, Casing};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    File, FnArg, LitStr, parse_macro_input, parse_quote, Path, PatType, ReturnType,
+};
+use syn::visit_mut::VisitMut;
+
+use crate::class::bridge_prefix::BridgePrefix;
+use crate::class::destructor::Destructor;
+use crate::class::extractor::AttributeExtractor;
+use crate::class::gen_vtable::{GenVTable, OnPanic};
+use crate::class::generic_base::{GenericBase, VirtualsAlias};
+use crate::class::generic_base_name::GenericBaseName;
+use crate::class::imp::Constructors;
+use crate::class::inherit::Inherited;
+use crate::class::pinned::Pinned;
+use crate::class::secondary_base::SecondaryBase;
+use crate::class::vtable_path::VTablePath;
+use crate::parse::{CppDef, ItemClass};
+use crate::util::{collect_secondary_bases, ElidedLifetimeNamer};
+
+mod base_access;
+mod bridge;
+mod bridge_prefix;
+mod destructor;
+mod extractor;
+mod gen_vtable;
+mod generic_base;
+mod generic_base_name;
+mod helper_macro;
+mod imp;
+mod info;
+mod inherit;
+mod pinned;
+mod placement;
+mod secondary_base;
+mod stct;
+mod trt;
+mod vtable;
+mod vtable_path;
+
+/// Returns the prefix that names the class's items from its helper macros.
+fn make_prefix(vtable_path: Option<&Path>) -> TokenStream {
+    vtable_path
+        .map(|vtable_path| quote!(#vtable_path::))
+        .unwrap_or_default()
+}
+
+/// Generates the Rust Struct, VTable struct and Virtuals struct.
+pub fn cpp_class_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut def = parse_macro_input!(input as CppDef);
+    let original = def.clone();
+    let mut output = proc_macro::TokenStream::default();
+
+    // see if there's a generic base replacement
+    if let Some(base_collections) = GenericBase::extract(&mut def.class) {
+        let name_template = GenericBaseName::extract(&mut def.class)
+            .unwrap_or_else(|| GenericBaseName::DEFAULT.to_owned());
+
+        // bases that take the generics name their instantiations by their own templates, so ask
+        // the next base if any are missing
+        let base_templates = generic_base_name::extract_base_templates(&mut def.class);
+        let family_bases = generic_base::collect_family_bases(
+            &def.class,
+            base_collections
+                .first()
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+        );
+        if let Some(base_path) = family_bases
+            .get(base_templates.len())
+            .and_then(|&idx| def.class.bases.path(idx))
+        {
+            let callback = generic_base::gen_family_callback(&original, base_path);
+            return callback.into_token_stream().into();
+        }
+
+        // name the instantiations through the generics of the class
+        let family = generic_base::gen_family(&def.class, &base_collections, &name_template);
+        output.extend([proc_macro::TokenStream::from(family.into_token_stream())]);
+
+        for generic_bases in base_collections {
+            let instance =
+                generic_base::instantiate(&def, &generic_bases, &name_template, &base_templates);
+            let family_impl =
+                generic_base::gen_family_impl(&def.class, &instance.class, &generic_bases);
+            let instance = generate_class(instance);
+            output.extend([
+                proc_macro::TokenStream::from(instance.into_token_stream()),
+                proc_macro::TokenStream::from(family_impl.into_token_stream()),
+            ]);
+        }
+    } else {
+        let def = generate_class(def);
+        output.extend([proc_macro::TokenStream::from(def.into_token_stream())]);
+    }
+
+    output
+}
+
+fn generate_class(mut def: CppDef) -> File {
+    let original = def.clone();
+
+    // extract `vtable_path`. the last path wins, so paths on the class override the crate default
+    let mut vtable_path = None;
+    while let Some(path) = VTablePath::extract(&mut def.class) {
+        vtable_path = Some(path);
+    }
+    let prefix = make_prefix(vtable_path.as_ref());
+
+    // extract the virtuals trait of the family, if the class is an instantiation of one
+    let virtuals_alias = VirtualsAlias::extract(&mut def.class);
+
+    // extract what the class inherits from its bases, asking the next base if any are missing
+    let inherited = inherit::extract_inherited(&mut def.class);
+    if let Some(base_path) = def.class.bases.path(inherited.len()) {
+        return inherit::gen_info_callback(&original, base_path);
+    }
+
+    // extract `gen_base`, which overrides the inherited secondary bases
+    let mut additional_bases = inherit::collect_inherited_bases(&def.class, &inherited);
+    additional_bases.extend(SecondaryBase::extract(&mut def.class).unwrap_or_default());
+
+    // extract `gen_vtable`
+    let gen_vtable = GenVTable::extract(&mut def.class);
+
+    // the class can only be defaulted in const contexts if its bases can
+    let const_bases = inherited.iter().all(|inherited| inherited.const_default);
+
+    // extract `pinned`. classes that derive from pinned classes are pinned too
+    let pinned = Pinned::extract(&mut def.class).is_some()
+        || inherited.iter().any(|inherited| inherited.pinned);
+    if pinned {
+        Pinned::add_field(&mut def.class);
+    }
+
+    // extract the virtual destructor, which classes without their own inherit from their bases
+    let destructor = Destructor::extract(&mut def.class, &inherited);
+
+    // extract `bridge_prefix`, and prefix the secondary bases whose virtuals would be hidden
+    let mut prefixed_bases = BridgePrefix::extract(&mut def.class).unwrap_or_default();
+    let virtuals = bridge_prefix::prefix_collisions(&def.class, &inherited, &mut prefixed_bases);
+
+    // the constructors of the bases, which are the only calls that can be passed the vtables
+    let base_constructors: Vec<_> = inherited
+        .iter()
+        .map(|inherited| inherited.constructors.clone())
+        .collect();
+
+    // determine the default ABI, falling back to that of the primary base
+    let abi = gen_vtable
+        .as_ref()
+        .and_then(|gen_vtable| gen_vtable.abi.clone())
+        .or_else(|| inherited.into_iter().next().map(|inherited| inherited.abi))
+        .unwrap_or_else(|| "C".to_owned());
+
+    // generate the base rust structure
+    let stct = stct::gen_struct(&def.class, &additional_bases, &base_constructors, const_bases);
+    let const_default = stct::has_const_default(&def.class, const_bases);
+
+    // generate the bridge between the class and its virtuals before standardizing the ABI
+    let bridge = bridge::gen_bridge(&def.class, &prefixed_bases, pinned);
+    let bridge_macro = bridge::gen_bridge_macro(&def.class, pinned, &prefix);
+
+    // standardize the ABI and signatures for virtuals before passing on the class
+    let on_panic = gen_vtable
+        .as_ref()
+        .and_then(|gen_vtable| gen_vtable.on_panic);
+    standardize_virtuals(
+        &mut def.class,
+        &prefix,
+        &abi,
+        on_panic == Some(OnPanic::Unwind),
+    );
+
+    // generate the trait
+    let policy = on_panic.and_then(OnPanic::policy);
+    let destructor_ident = destructor.as_ref().and_then(Destructor::virtual_ident);
+    let trt = if let Some(gen_vtable) = &gen_vtable {
+        Some(trt::gen_trait(
+            &def.class,
+            gen_vtable.no_unimpl.is_present(),
+            policy.is_some(),
+            destructor_ident,
+        ))
+    } else {
+        None
+    };
+    let unimpl_macro = gen_vtable
+        .is_some()
+        .then(|| trt::gen_unimpl_macro(&def.class, policy.is_some(), destructor_ident, &prefix));
+    let alias_impl = virtuals_alias
+        .filter(|_| gen_vtable.is_some())
+        .map(|alias| trt::gen_alias_impl(&def.class, &alias, policy.is_some(), destructor_ident));
+
+    // generate the VTable structure
+    let destruct = destructor
+        .as_ref()
+        .map(|destructor| destructor.gen_destruct(pinned));
+    let vtable = vtable::gen_vtable(
+        &def.class,
+        &additional_bases,
+        gen_vtable.is_some(),
+        destruct.as_ref(),
+        destructor_ident,
+        policy.as_ref(),
+    );
+    let vtable_macro = vtable::gen_vtable_macro(
+        &def.class,
+        &vtable::sort_virtuals(&def.class),
+        policy.as_ref(),
+        &prefix,
+    );
+
+    // generate implementation hooks
+    let (impl_hooks, mut constructors) =
+        imp::gen_hooks(&def, &additional_bases, base_constructors, pinned);
+    if stct::derives_default(&def.class.attrs) {
+        let default_ident = format_ident!("default");
+        constructors.idents.push(default_ident.clone());
+        constructors.placeable.push(default_ident);
+    }
+    let rebind = imp::gen_rebind(&def.class, &additional_bases);
+
+    // generate access helpers
+    let access_helpers = base_access::gen_base_helpers(&def.class, pinned);
+
+    // generate reflection metadata
+    let class_info = info::gen_class_info(&def.class, gen_vtable.is_some());
+
+    // pass on the defaults to derived classes
+    let passed_on = Inherited {
+        abi,
+        bases: collect_secondary_bases(&def.class, &additional_bases)
+            .into_iter()
+            .cloned()
+            .collect(),
+        const_default,
+        pinned,
+        destructor: destructor.is_some(),
+        virtuals,
+        constructors: Constructors {
+            bases: Vec::new(),
+            ..constructors
+        },
+    };
+    let info_arm = inherit::gen_info_macro(&def.class, &passed_on, &prefix);
+
+    // collect the helpers that derived classes and implementors expand into the class's macro
+    let mut arms = vec![vtable_macro, bridge_macro, info_arm];
+    arms.extend(unimpl_macro);
+    let helper_macro = helper_macro::gen_helper_macro(&def.class, &arms);
+
+    let output = quote! {
+        #[allow(non_camel_case_types)]
+        #stct
+        #(#impl_hooks)*
+        #rebind
+        #[allow(non_camel_case_types)]
+        #trt
+        #alias_impl
+        #vtable
+        #bridge
+        #access_helpers
+        #class_info
+        #helper_macro
+    };
+    syn::parse(output.into()).expect("failed to generate class")
+}
+
+/// Makes the base identifier for a type.
+fn make_base_name(ident: &Ident) -> Ident {
+    format_ident!("base_{}", ident.to_string().to_case(Case::Snake))
+}
+
+/// Standardizes the ABI and signatures for virtuals. Virtuals without an ABI use the class's
+/// default ABI, or its unwinding variant if panics are allowed to unwind. The class's lifetimes
+/// are declared by each signature, since a VTable is shared by instances of any lifetime.
+fn standardize_virtuals(class: &mut ItemClass, prefix: &TokenStream, abi: &str, unwind: bool) {
+    let generic_args = (!class.generics.params.is_empty()).then(|| class.generic_args());
+    let lifetimes = class.lifetimes();
+    for virt in class.body.virtuals.iter_mut() {
+        if virt.sig.abi.is_none() {
+            let abi = if unwind {
+                LitStr::new(&format!("{abi}-unwind"), Span::call_site())
+            } else {
+                LitStr::new(abi, Span::call_site())
+            };
+            virt.sig.abi = parse_quote!(extern #abi);
+        }
+
+        // if the first arg is `self`, replace it with the type
+        if let Some(FnArg::Receiver(receiver)) = virt.sig.inputs.first().cloned() {
+            let class_ident = &class.ident;
+            let mutability = receiver.mutability;
+            let mut self_lifetime = receiver.lifetime().cloned();
+
+            // the class's lifetimes make elision ambiguous, so name the receiver's lifetime
+            if !lifetimes.is_empty() && self_lifetime.is_none() {
+                let mut namer = ElidedLifetimeNamer::new(parse_quote!('__self));
+                if let ReturnType::Type(_, ty) = &mut virt.sig.output {
+                    namer.visit_type_mut(ty);
+                }
+                if namer.named {
+                    virt.sig.generics.params.insert(0, parse_quote!('__self));
+                    self_lifetime = Some(namer.lifetime);
+                }
+            }
+            for lifetime in lifetimes.iter().rev() {
+                virt.sig.generics.params.insert(0, parse_quote!(#lifetime));
+            }
+
+            let args = &mut virt.sig.inputs;
+            *args.first_mut().unwrap() = FnArg::Typed(PatType {
+                attrs: vec![],
+                pat: Box::new(parse_quote!(this)),
+                colon_token: Default::default(),
+                ty: Box::new(parse_quote!(
+                    &#self_lifetime #mutability #prefix #class_ident #generic_args
+                )),
+            });
+        } else {
+            panic!("virtuals must take `&self` or `&mut self`")
+        }
+    }
+}
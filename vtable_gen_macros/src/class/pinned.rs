@@ -0,0 +1,47 @@
+use proc_macro2::Ident;
+use quote::format_ident;
+use syn::{Attribute, Field, parse_quote};
+
+use crate::class::extractor::AttributeExtractor;
+use crate::parse::ItemClass;
+
+/// Classes whose instances must not move after construction, like C++ objects that store `this`.
+pub struct Pinned;
+
+impl Pinned {
+    /// Makes the identifier of the marker field that keeps the class from being `Unpin`.
+    pub fn make_field_ident() -> Ident {
+        format_ident!("_pinned")
+    }
+
+    /// Adds the marker field to the class.
+    pub fn add_field(class: &mut ItemClass) {
+        let field_ident = Self::make_field_ident();
+        let field: Field = parse_quote!(pub #field_ident: ::core::marker::PhantomPinned);
+        class.body.fields.push(field);
+        class.body.fields.push_punct(Default::default());
+    }
+
+    /// Returns true if the class has the marker field.
+    pub fn has_field(class: &ItemClass) -> bool {
+        let field_ident = Self::make_field_ident();
+        class
+            .body
+            .fields
+            .iter()
+            .any(|field| field.ident.as_ref() == Some(&field_ident))
+    }
+}
+
+impl AttributeExtractor for Pinned {
+    type Output = ();
+
+    fn attr() -> &'static str {
+        "pinned"
+    }
+
+    fn parse_attr(attr: Attribute) -> syn::Result<Self::Output> {
+        attr.meta.require_path_only()?;
+        Ok(())
+    }
+}
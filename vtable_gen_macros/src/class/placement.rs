@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use proc_macro2::Ident;
+use quote::format_ident;
+use syn::{
+    Block, Expr, ExprBlock, ExprIf, ExprMatch, ExprParen, ExprReturn, ExprStruct, ExprUnsafe,
+    ImplItemFn, Item, Member, parse_quote, Path, Stmt,
+};
+use syn::visit_mut::{self, VisitMut};
+
+use crate::class::imp::{self, Constructors};
+use crate::class::make_base_name;
+use crate::class::pinned::Pinned;
+use crate::parse::ItemClass;
+use crate::util::{extract_ident, make_hygienic_ident};
+
+/// Makes the identifier of the function that constructs the class in place like a constructor.
+/// `new` is `construct_at`.
+pub fn make_construct_at_ident(ident: &Ident) -> Ident {
+    if ident == "new" {
+        format_ident!("construct_at")
+    } else {
+        format_ident!("construct_{ident}_at")
+    }
+}
+
+/// Generates the functions that construct the class in place like a constructor: one that takes
+/// the vtables and one that passes the class's own.
+pub fn gen_construct_at(
+    class: &ItemClass,
+    func: &ImplItemFn,
+    constructors: &Constructors,
+    additional_bases: &HashMap<Path, Vec<Path>>,
+) -> [ImplItemFn; 2] {
+    let vis = &func.vis;
+    let abi = &func.sig.abi;
+    let ident = &func.sig.ident;
+    let args = func.sig.inputs.iter().collect_vec();
+    let arg_names = imp::collect_arg_names(func);
+
+    let construct_ident = make_construct_at_ident(ident);
+    let proxy_ident = imp::make_ctor_call(&construct_ident);
+    let vtable_params = imp::make_vtable_params(class, additional_bases);
+    let accessor_calls = imp::make_vtable_accessor_calls(class, additional_bases);
+    let slot = make_slot_ident();
+    let ptr = make_hygienic_ident("ptr");
+    let where_clause = imp::make_static_where_clause(class);
+
+    // the instances are written into the slot wherever the constructor produces them. if it can't
+    // be followed, the instance it produces is moved into the slot instead
+    let mut block = func.block.clone();
+    let mut placer = Placer {
+        class,
+        constructors,
+        additional_bases,
+        placed: true,
+    };
+    placer.visit_block_mut(&mut block);
+    let block: Block = if placer.placed && placer.place_block(&mut block) {
+        imp::hook_block(
+            class,
+            &mut block,
+            &constructors.idents,
+            &constructors.bases,
+            additional_bases,
+            ident,
+        );
+        parse_quote!({
+            let #slot: *mut Self = #slot.cast();
+            #block
+        })
+    } else {
+        let ctor_ident = imp::make_ctor_call(ident);
+        let vtable_args = imp::make_vtable_args(class, additional_bases);
+        parse_quote!({
+            unsafe {
+                (*#slot).write(Self::#ctor_ident(#(#arg_names,)* #(#vtable_args),*));
+            }
+        })
+    };
+
+    [
+        parse_quote! {
+            #[doc(hidden)]
+            #vis unsafe #abi fn #proxy_ident(
+                #slot: *mut ::core::mem::MaybeUninit<Self>,
+                #(#args,)*
+                #(#vtable_params),*
+            ) #block
+        },
+        parse_quote! {
+            /// Constructs an instance in place, without moving it.
+            ///
+            /// # Safety
+            /// `ptr` must be valid for writes and properly aligned.
+            #vis unsafe #abi fn #construct_ident(
+                #ptr: *mut ::core::mem::MaybeUninit<Self>,
+                #(#args),*
+            ) #where_clause {
+                Self::#proxy_ident(#ptr, #(#arg_names,)* #(#accessor_calls),*)
+            }
+        },
+    ]
+}
+
+/// Writes the instances produced by a constructor into the slot, field by field.
+struct Placer<'a> {
+    class: &'a ItemClass,
+    constructors: &'a Constructors,
+    additional_bases: &'a HashMap<Path, Vec<Path>>,
+    /// Whether every returned instance was written into the slot.
+    placed: bool,
+}
+
+impl VisitMut for Placer<'_> {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            // closures return their own values
+            Expr::Closure(_) | Expr::Async(_) => {}
+            Expr::Return(ExprReturn {
+                expr: Some(value), ..
+            }) => self.placed &= self.place(value),
+            _ => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_item_mut(&mut self, _item: &mut Item) {}
+}
+
+impl Placer<'_> {
+    /// Writes the instance produced by the block into the slot. Returns true if it could.
+    fn place_block(&mut self, block: &mut Block) -> bool {
+        match block.stmts.last_mut() {
+            // the writes of an instantiation become the block's own statements
+            Some(Stmt::Expr(Expr::Struct(stct), None))
+                if imp::is_instantiation(self.class, stct) =>
+            {
+                let Some(writes) = self.make_writes(stct) else {
+                    return false;
+                };
+                block.stmts.pop();
+                block.stmts.extend(writes);
+                true
+            }
+            Some(Stmt::Expr(expr, None)) => self.place(expr),
+            Some(Stmt::Expr(Expr::Return(_), Some(_))) => true,
+            _ => false,
+        }
+    }
+
+    /// Writes the instance produced by the expression into the slot. Returns true if it could.
+    fn place(&mut self, expr: &mut Expr) -> bool {
+        match expr {
+            Expr::Struct(stct) if imp::is_instantiation(self.class, stct) => {
+                let Some(writes) = self.make_writes(stct) else {
+                    return false;
+                };
+                *expr = parse_quote!({ #(#writes)* });
+                true
+            }
+            Expr::Call(call) => {
+                // other constructors construct the instance in place themselves
+                let placeable = &self.constructors.placeable;
+                let Some(fn_segment) = imp::as_delegation(self.class, call, placeable) else {
+                    return false;
+                };
+                fn_segment.ident = imp::make_ctor_call(&make_construct_at_ident(&fn_segment.ident));
+                let slot = make_slot_ident();
+                call.args.insert(0, parse_quote!(#slot.cast()));
+                call.args
+                    .extend(imp::make_vtable_args(self.class, self.additional_bases));
+                *expr = parse_quote!(unsafe { #call });
+                true
+            }
+            Expr::Block(ExprBlock { block, .. }) | Expr::Unsafe(ExprUnsafe { block, .. }) => {
+                self.place_block(block)
+            }
+            Expr::If(ExprIf {
+                then_branch,
+                else_branch,
+                ..
+            }) => {
+                self.place_block(then_branch)
+                    && else_branch
+                        .as_mut()
+                        .is_some_and(|(_, else_branch)| self.place(else_branch))
+            }
+            Expr::Match(ExprMatch { arms, .. }) => {
+                arms.iter_mut().all(|arm| self.place(&mut arm.body))
+            }
+            Expr::Paren(ExprParen { expr, .. }) => self.place(expr),
+            // returns are placed wherever they are
+            Expr::Return(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Makes the writes of an instantiation's fields into the slot. Bases constructed through
+    /// their constructors are constructed in place. Instantiations that leave fields to a struct
+    /// update, or to the compiler to complain about, can't be placed.
+    fn make_writes(&self, expr: &ExprStruct) -> Option<Vec<Stmt>> {
+        let is_set = |ident: &Ident| {
+            expr.fields
+                .iter()
+                .any(|field| matches!(&field.member, Member::Named(member) if member == ident))
+        };
+        let pinned_ident = Pinned::make_field_ident();
+        let fields_set = self
+            .class
+            .body
+            .fields
+            .iter()
+            .filter_map(|field| field.ident.as_ref())
+            .filter(|ident| **ident != pinned_ident)
+            .all(is_set);
+        let bases_set = self
+            .class
+            .bases
+            .paths()
+            .all(|base_ty| is_set(&make_base_ident(base_ty)));
+        if expr.rest.is_some() || !fields_set || !bases_set {
+            return None;
+        }
+
+        let mut writes: Vec<Stmt> = Vec::new();
+        if self.class.bases.is_empty() {
+            writes.push(make_write(
+                &parse_quote!(vfptr),
+                &parse_quote!(::vtable_gen::VfPtr::new(vfptr)),
+            ));
+        }
+        if Pinned::has_field(self.class) && !is_set(&pinned_ident) {
+            writes.push(make_write(
+                &Member::Named(pinned_ident),
+                &parse_quote!(::core::marker::PhantomPinned),
+            ));
+        }
+        for field in &expr.fields {
+            let base = self
+                .class
+                .bases
+                .paths()
+                .enumerate()
+                .find(|(_, base_ty)| field.member == Member::Named(make_base_ident(base_ty)));
+            let write = match base {
+                Some((idx, base_ty)) => {
+                    self.make_base_write(idx, base_ty, &field.member, &field.expr)
+                }
+                None => make_write(&field.member, &field.expr),
+            };
+            writes.push(write);
+        }
+
+        Some(writes)
+    }
+
+    /// Makes the write of a base into the slot, constructing it in place if it's constructed
+    /// through one of its constructors.
+    fn make_base_write(&self, idx: usize, base_ty: &Path, member: &Member, value: &Expr) -> Stmt {
+        let vtable_args = imp::make_base_vtable_args(idx, base_ty, self.additional_bases);
+        let mut value = value.clone();
+        let Some(call) = imp::as_base_constructor(
+            &mut value,
+            base_ty,
+            &self.constructors.bases[idx].placeable,
+        ) else {
+            return make_write(member, &imp::make_rebind(&value, base_ty, &vtable_args));
+        };
+
+        let Expr::Path(fn_path) = &mut *call.func else {
+            unreachable!()
+        };
+        let fn_segment = fn_path.path.segments.last_mut().unwrap();
+        fn_segment.ident = imp::make_ctor_call(&make_construct_at_ident(&fn_segment.ident));
+        let slot = make_slot_ident();
+        call.args.insert(
+            0,
+            parse_quote!(::core::ptr::addr_of_mut!((*#slot).#member).cast()),
+        );
+        call.args.extend(vtable_args);
+        parse_quote!(unsafe { #call };)
+    }
+}
+
+/// Makes the identifier of the field of a base.
+fn make_base_ident(base_ty: &Path) -> Ident {
+    make_base_name(extract_ident(base_ty))
+}
+
+/// Makes the write of a field into the slot.
+fn make_write(member: &Member, value: &Expr) -> Stmt {
+    let slot = make_slot_ident();
+    parse_quote! {
+        unsafe { ::core::ptr::addr_of_mut!((*#slot).#member).write(#value) };
+    }
+}
+
+/// Makes the identifier of the slot the instance is constructed in, which the constructor's own
+/// arguments can't clash with.
+fn make_slot_ident() -> Ident {
+    make_hygienic_ident("slot")
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use quote::quote;
+use syn::{Attribute, Expr, FieldValue, File, Meta, parse_quote, Path, Token, Type, TypePath};
+use syn::punctuated::Punctuated;
+
+use crate::class::{imp, make_base_name};
+use crate::class::imp::Constructors;
+use crate::class::vtable::make_vtable_ident;
+use crate::parse::ItemClass;
+use crate::util::extract_ident;
+
+/// Generates the base structure. `const_bases` is whether all bases can be defaulted in const
+/// contexts.
+pub fn gen_struct(
+    class: &ItemClass,
+    additional_bases: &HashMap<Path, Vec<Path>>,
+    base_constructors: &[Constructors],
+    const_bases: bool,
+) -> File {
+    let mut attrs = class.attrs.clone();
+
+    let const_default = has_const_default(class, const_bases);
+    let default_impl = intercept_default(
+        class,
+        &mut attrs,
+        additional_bases,
+        base_constructors,
+        const_default,
+    );
+
+    let vis = &class.vis;
+    let ident = &class.ident;
+    let generics = &class.generics;
+    let where_clause = &generics.where_clause;
+
+    // add the bases to the fields list
+    let mut fields = class.body.fields.clone();
+    for (idx, (base_ty, _)) in class.bases.bases.iter().enumerate().rev() {
+        let base_ident = make_base_name(class.bases.ident(idx).unwrap());
+        // TODO: add visibility specifiers to definitions
+        fields.insert(0, parse_quote!(pub #base_ident: #base_ty));
+    }
+
+    // add the vtable if there aren't any bases and there are virtuals.
+    if class.bases.bases.is_empty() && !class.body.virtuals.is_empty() {
+        // push the VTable member
+        let generic_args = class.vtable_generic_args();
+        let vtable_ty = make_vtable_ident(ident);
+        fields.insert(
+            0,
+            parse_quote!(pub vfptr: ::vtable_gen::VfPtr<#vtable_ty #generic_args>),
+        );
+    }
+
+    // non-virtual bases are not supported because we don't have a way
+    // of knowing if the first base class is virtual or not otherwise,
+    // and we wouldn't know if we need to generate a vtable
+    if class.body.virtuals.is_empty() && class.bases.bases.is_empty() {
+        panic!("non-virtual base-classes are not supported")
+    }
+
+    // if there's not `#[repr(C)]`, add it
+    if !has_repr_c(&attrs) {
+        attrs.push(parse_quote!(#[repr(C)]));
+    }
+
+    syn::parse(
+        quote! {
+            #(#attrs)*
+            #vis struct #ident #generics #where_clause {
+                #fields
+            }
+
+            #default_impl
+        }
+        .into(),
+    )
+    .expect("failed to generate base struct")
+}
+
+/// Checks an attribute list for `repr(C)`
+fn has_repr_c(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        if !attr.path().is_ident("repr") {
+            return false;
+        }
+
+        // parse metas
+        let nested = attr
+            .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            .expect("Failed to parse repr");
+
+        nested.iter().any(|meta| meta.path().is_ident("C"))
+    })
+}
+
+/// Returns true if the class derives `Default`, and its fields and bases can all be defaulted in
+/// const contexts.
+pub fn has_const_default(class: &ItemClass, const_bases: bool) -> bool {
+    const_bases
+        && derives_default(&class.attrs)
+        && class
+            .body
+            .fields
+            .iter()
+            .all(|field| make_const_default(&field.ty).is_some())
+}
+
+/// Checks an attribute list for `derive(Default)`
+pub fn derives_default(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("derive")
+            && attr
+                .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .is_ok_and(|nested| nested.iter().any(|meta| meta.path().is_ident("Default")))
+    })
+}
+
+/// Makes the default value of a type that can be evaluated in const contexts, if there's one.
+fn make_const_default(ty: &Type) -> Option<Expr> {
+    match ty {
+        Type::Paren(ty) => make_const_default(&ty.elem),
+        Type::Tuple(tuple) => {
+            let elems = tuple
+                .elems
+                .iter()
+                .map(make_const_default)
+                .collect::<Option<Vec<_>>>()?;
+            Some(parse_quote!((#(#elems,)*)))
+        }
+        Type::Reference(reference)
+            if reference.mutability.is_none()
+                && matches!(&*reference.elem, Type::Path(ty) if ty.path.is_ident("str")) =>
+        {
+            Some(parse_quote!(""))
+        }
+        Type::Path(TypePath { qself: None, path }) => {
+            let segment = path.segments.last()?;
+            match segment.ident.to_string().as_str() {
+                "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64"
+                | "i128" | "isize" => Some(parse_quote!(0)),
+                "f32" | "f64" => Some(parse_quote!(0.0)),
+                "bool" => Some(parse_quote!(false)),
+                "char" => Some(parse_quote!('\0')),
+                "Option" => Some(parse_quote!(::core::option::Option::None)),
+                "PhantomData" => Some(parse_quote!(::core::marker::PhantomData)),
+                "PhantomPinned" => Some(parse_quote!(::core::marker::PhantomPinned)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Intercepts `#[derive(Default)]` and implements it ourselves. If `const_default` is set, the
+/// implementation can be evaluated in const contexts, and is exposed as `DEFAULT`.
+fn intercept_default(
+    class: &ItemClass,
+    attrs: &mut [Attribute],
+    additional_bases: &HashMap<Path, Vec<Path>>,
+    base_constructors: &[Constructors],
+    const_default: bool,
+) -> Option<File> {
+    // see if there's a `derive` attribute
+    let derive_attr = attrs
+        .iter_mut()
+        .find(|attr| attr.path().is_ident("derive"))?;
+
+    // find `Default`
+    let meta = derive_attr
+        .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+        .expect("Failed to parse repr");
+
+    // remove it... maybe someday we'll be able to do this
+    let default_idx = meta
+        .iter()
+        .position(|meta| meta.path().is_ident("Default"))?;
+
+    let mut new_meta = Punctuated::<Meta, Token![,]>::new();
+    for (idx, meta) in meta.into_iter().enumerate() {
+        if idx == default_idx {
+            continue;
+        }
+
+        new_meta.push(meta);
+    }
+
+    // replace the old meta
+    derive_attr.meta = parse_quote!(derive(#new_meta));
+
+    // generate the implementation. start with a naive implementation
+    let fields: Vec<FieldValue> = class
+        .body
+        .fields
+        .iter()
+        .filter_map(|field| Some((field.ident.as_ref()?, &field.ty)))
+        .map(
+            |(field_name, ty)| match make_const_default(ty).filter(|_| const_default) {
+                Some(value) => parse_quote!(#field_name: #value),
+                None => parse_quote!(#field_name: ::core::default::Default::default()),
+            },
+        )
+        .chain(class.bases.paths().map(|base_path| {
+            let base_ident = make_base_name(extract_ident(base_path));
+            parse_quote!(#base_ident: <#base_path>::default())
+        }))
+        .collect_vec();
+
+    let default_fn = parse_quote! {
+        fn default() -> Self {
+            Self {
+                #(#fields),*
+            }
+        }
+    };
+    let (impl_generics, _, where_clause) = class.generics.split_for_impl();
+    let static_generics = class.static_generics();
+    let (_, _, static_where_clause) = static_generics.split_for_impl();
+    let generic_args = class.generic_args();
+    let ident = &class.ident;
+    let constructors = Constructors {
+        bases: base_constructors.to_vec(),
+        ..Default::default()
+    };
+    let [impl_fn, default_fn, construct_fns @ ..] =
+        &mut imp::hook_fn(class, default_fn, &constructors, additional_bases, false)[..]
+    else {
+        unreachable!()
+    };
+    impl_fn.vis = parse_quote!(pub);
+    for construct_fn in construct_fns.iter_mut() {
+        construct_fn.vis = parse_quote!(pub);
+    }
+
+    // the vtables of generic classes are only known at runtime
+    let const_item = if const_default {
+        impl_fn.sig.constness = Some(Default::default());
+        class.vtable_generic_args().args.is_empty().then(|| {
+            let accessor_calls = imp::make_vtable_accessor_calls(class, additional_bases);
+            quote! {
+                /// The default value of the class, usable in const contexts.
+                pub const DEFAULT: Self = Self::_default_with_vtable(#(#accessor_calls),*);
+            }
+        })
+    } else {
+        None
+    };
+
+    let output = quote! {
+        impl #impl_generics #ident #generic_args #where_clause {
+            #const_item
+            #impl_fn
+            #(#construct_fns)*
+        }
+
+        impl #impl_generics ::core::default::Default for #ident #generic_args #static_where_clause {
+            #default_fn
+        }
+    };
+    Some(syn::parse(output.into()).expect("failed to generate default implementation"))
+}
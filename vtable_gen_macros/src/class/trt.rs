@@ -0,0 +1,254 @@
+use itertools::Itertools;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{File, FnArg, ItemImpl, parse_quote, Pat, Path, TraitItemFn, Type};
+
+use crate::class::helper_macro::Helper;
+use crate::parse::ItemClass;
+use crate::util::{
+    extract_implementor_generics, last_segment_mut, strip_lifetimes, make_base_macro_path,
+    make_generic_matchers, make_macro_prefix, qualify_base_module, qualify_path,
+    replace_crate_paths,
+};
+
+/// Generates the virtuals trait for the type. If the VTable slots are guarded against panics, the
+/// functions use the Rust ABI, since the shims in the slots provide the virtual's ABI.
+pub fn gen_trait(
+    class: &ItemClass,
+    no_unimpl: bool,
+    guarded: bool,
+    destructor: Option<&Ident>,
+) -> File {
+    let vis = &class.vis;
+    let generics = class.vtable_generics();
+    let where_clause = &generics.where_clause;
+    let virtuals_ident = make_virtuals(&class.ident);
+
+    // collect base trait identifiers
+    let base_traits = collect_base_traits(class);
+
+    // collect trait functions
+    let trait_functions = collect_functions(class, guarded, destructor);
+
+    // call the macro if needed
+    let trait_impl = if !no_unimpl {
+        let selector = Helper::Unimpl.selector();
+        let struct_ident = &class.ident;
+        let struct_generic_args = class.generic_args_with_lifetime(&parse_quote!('_));
+        let def_generic_args = class.vtable_generic_args();
+        Some(
+            quote!(#struct_ident!(#selector [] #struct_ident #struct_generic_args, #def_generic_args);),
+        )
+    } else {
+        None
+    };
+
+    let output = quote! {
+        #vis trait #virtuals_ident #generics: #(#base_traits)+* #where_clause {
+            #(#trait_functions)*
+        }
+
+        #trait_impl
+    };
+    syn::parse(output.into()).expect("failed to generate trait")
+}
+
+/// Implements the virtuals trait for each implementor of the family's virtuals trait, which
+/// `alias` names for the instantiation, by forwarding to it.
+pub fn gen_alias_impl(
+    class: &ItemClass,
+    alias: &Path,
+    guarded: bool,
+    destructor: Option<&Ident>,
+) -> ItemImpl {
+    let virtuals_ident = make_virtuals(&class.ident);
+    let generic_args = class.vtable_generic_args();
+    let base_traits = collect_base_traits(class);
+
+    let mut generics = class.vtable_generics();
+    generics.params.insert(0, parse_quote!(__Implementor));
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(__Implementor: #alias #(+ #base_traits)*));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let forwarders = collect_functions(class, guarded, destructor).into_iter().map(|func| {
+        let sig = &func.sig;
+        let ident = &sig.ident;
+        let args = sig.inputs.iter().map(|arg| match arg {
+            FnArg::Typed(arg) => match &*arg.pat {
+                Pat::Ident(ident) => &ident.ident,
+                _ => panic!("virtual args must have identifiers"),
+            },
+            FnArg::Receiver(_) => panic!("virtuals take the class in place of `self`"),
+        });
+        let call = quote!(<__Implementor as #alias>::#ident(#(#args),*));
+        let call = if sig.unsafety.is_some() { quote!(unsafe { #call }) } else { call };
+        quote! {
+            #sig {
+                #call
+            }
+        }
+    });
+
+    parse_quote! {
+        impl #impl_generics #virtuals_ident #generic_args for __Implementor #where_clause {
+            #(#forwarders)*
+        }
+    }
+}
+
+/// Makes a class identifier refer to its virtuals trait.
+pub fn make_virtuals(ident: &Ident) -> Ident {
+    format_ident!("{}Virtuals", ident)
+}
+
+/// Collects a list of base trait identifiers.
+fn collect_base_traits(class: &ItemClass) -> Vec<Path> {
+    class
+        .bases
+        .bases
+        .iter()
+        .map(|(base, _)| {
+            let mut base = strip_lifetimes(base);
+            let segment = last_segment_mut(&mut base);
+            segment.ident = make_virtuals(&segment.ident);
+            base
+        })
+        .collect()
+}
+
+/// Collects all functions as trait item functions.
+pub fn collect_functions(
+    class: &ItemClass,
+    guarded: bool,
+    destructor: Option<&Ident>,
+) -> Vec<TraitItemFn> {
+    class
+        .body
+        .virtuals
+        .iter()
+        .map(|virt| {
+            let mut sig = virt.sig.clone();
+            // remove unsafety to keep strict safety for trait implementations. the destructor keeps
+            // it, since calling it leaves the instance destroyed
+            if destructor != Some(&sig.ident) {
+                sig.unsafety = None;
+            }
+            if guarded {
+                sig.abi = None;
+            }
+            TraitItemFn {
+                attrs: vec![],
+                sig,
+                default: None,
+                semi_token: None,
+            }
+        })
+        .collect()
+}
+
+/// Generates the helper macro arm that implements the class's virtuals, and those of its bases,
+/// with `unimplemented!()`.
+pub fn gen_unimpl_macro(
+    class: &ItemClass,
+    guarded: bool,
+    destructor: Option<&Ident>,
+    prefix: &TokenStream,
+) -> TokenStream {
+    let struct_ident = &class.ident;
+    let generic_args = class.vtable_generic_args().args;
+    let lifetimes = class.lifetimes();
+    // collect all generic args into descriptors
+    let def_generic_arg_idents = generic_args
+        .iter()
+        .enumerate()
+        .map(|(idx, _)| format_ident!("def_generic_{idx}"))
+        .collect_vec();
+    let def_generic_matchers = make_generic_matchers(class);
+    let selector = Helper::Unimpl.selector();
+    let virtuals_ident = make_virtuals(struct_ident);
+    let prefix = make_macro_prefix(prefix);
+
+    let impls = class
+        .body
+        .virtuals
+        .iter()
+        .map(|virt| {
+            let mut sig = virt.sig.clone();
+            // remove the unsafety, except from the destructor
+            if destructor != Some(&sig.ident) {
+                sig.unsafety = None;
+            }
+            if guarded {
+                sig.abi = None;
+            }
+
+            // underscore all args
+            for input in &mut sig.inputs {
+                if let FnArg::Typed(arg) = input {
+                    if let Pat::Ident(ident) = &mut *arg.pat {
+                        ident.ident = format_ident!("_{}", ident.ident);
+                    }
+                }
+            }
+
+            // name the class relative to the caller
+            let (self_lifetime, mutability) = match sig.inputs.first() {
+                Some(FnArg::Typed(arg)) => match &*arg.ty {
+                    Type::Reference(reference) => {
+                        (reference.lifetime.clone(), reference.mutability)
+                    }
+                    _ => (None, None),
+                },
+                _ => (None, None),
+            };
+            let args = sig.inputs.iter().skip(1);
+            let unsafety = &sig.unsafety;
+            let abi = &sig.abi;
+            let ident = &sig.ident;
+            let generics = &sig.generics;
+            let output = &sig.output;
+            quote!(
+                #unsafety #abi fn #ident #generics(
+                    _this: &#self_lifetime #mutability #prefix #struct_ident <#(#lifetimes,)* #($#def_generic_arg_idents),*>,
+                    #(#args),*
+                ) #output {
+                    ::core::unimplemented!()
+                }
+            )
+        })
+        .collect_vec();
+
+    // generate the base vtable
+    let additional_impls = class
+        .bases
+        .paths()
+        .map(|base_path| {
+            let macro_path = qualify_path(&prefix, &make_base_macro_path(base_path));
+            let base_module = qualify_base_module(&prefix, base_path);
+
+            // determine the position of each and extract it out of the parent definition
+            let base_def_args = extract_implementor_generics(class, base_path);
+            quote!(#macro_path!(#selector [#base_module] $implementor_ty, <#(#base_def_args),*>))
+        })
+        .collect_vec();
+
+    let body = replace_crate_paths(quote! {
+        #(#additional_impls;)*
+
+        impl #prefix #virtuals_ident <#($#def_generic_arg_idents),*> for $implementor_ty {
+            #(#impls)*
+        }
+    });
+
+    quote! {
+        // module: The path of the module the class lives in, relative to the caller.
+        // implementor_ty: The type of the implementor.
+        // gen_x: The definition generic at position `x`.
+        (#selector [$($module:tt)*] $implementor_ty:ty, <#(#def_generic_matchers),*>) => {
+            #body
+        };
+    }
+}
@@ -0,0 +1,577 @@
+use std::collections::{BTreeMap, HashMap};
+use std::iter;
+
+use convert_case::{Case, Casing};
+use itertools::Itertools;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    AngleBracketedGenericArguments, Field, FieldMutability, File, FnArg, GenericParam, ImplItemFn,
+    ItemConst, ItemImpl, LifetimeParam, LitStr, parse_quote, Path, PathArguments, ReturnType,
+};
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::visit_mut::VisitMut;
+
+use crate::class::helper_macro::Helper;
+use crate::class::make_base_name;
+use crate::class::trt::make_virtuals;
+use crate::parse::{ItemClass, Virtual};
+use crate::util::{
+    collect_lifetimes, collect_secondary_bases, ElidedLifetimeNamer, extract_ident,
+    extract_implementor_generics, last_segment, LifetimeEliser, make_base_macro_path,
+    make_base_module, make_base_vtable_ty, make_generic_matchers,
+    make_macro_prefix, qualify_base_module, qualify_path, replace_crate_paths, strip_lifetimes,
+};
+
+/// Generates a VTable for the class.
+pub fn gen_vtable(
+    class: &ItemClass,
+    additional_bases: &HashMap<Path, Vec<Path>>,
+    gen_vtable: bool,
+    destruct: Option<&ImplItemFn>,
+    destructor: Option<&Ident>,
+    policy: Option<&TokenStream>,
+) -> File {
+    let virtuals = sort_virtuals(class);
+
+    // generate the vtable structure
+    let vtable = gen_vtable_struct(class, &virtuals);
+
+    // generate the vtable static
+    let stc = if gen_vtable {
+        Some(gen_vtable_static(class, additional_bases, destruct))
+    } else {
+        None
+    };
+
+    // generate the shims guarding the slots
+    let shims = policy.map(|_| gen_vtable_shims(class, &virtuals, destructor));
+
+    syn::parse(
+        quote! {
+            #vtable
+            #stc
+            #shims
+        }
+        .into(),
+    )
+    .expect("failed to generate vtable")
+}
+
+/// Make the VTable struct identifier.
+pub fn make_vtable_ident(ident: &Ident) -> Ident {
+    format_ident!("{ident}VTable")
+}
+
+/// Make the VTable static identifier for a base class. Only used for secondary implementations.
+pub fn make_vtable_static(
+    ident: &Ident,
+    base: &Ident,
+    generics: &AngleBracketedGenericArguments,
+) -> Path {
+    let vtable_ident = format_ident!(
+        "VTBL_FOR_{}",
+        base.to_string().to_case(Case::ScreamingSnake)
+    );
+    parse_quote!(#ident :: #generics :: #vtable_ident)
+}
+
+/// Make the path of the function that returns the unique VTable static for a base class.
+pub fn make_vtable_accessor(
+    ident: &Ident,
+    base: &Ident,
+    generics: &AngleBracketedGenericArguments,
+) -> Path {
+    let accessor_ident = format_ident!("vtbl_for_{}", base.to_string().to_case(Case::Snake));
+    parse_quote!(#ident :: #generics :: #accessor_ident)
+}
+
+/// Generates the helper macro arm that populates the VTable for `class`. If the class has a panic
+/// policy, the slots are populated with shims that catch panics instead of the implementations
+/// themselves.
+pub fn gen_vtable_macro(
+    class: &ItemClass,
+    virtuals: &BTreeMap<usize, Virtual>,
+    policy: Option<&TokenStream>,
+    prefix: &TokenStream,
+) -> TokenStream {
+    let class_ident = &class.ident;
+    let virtuals_ident = make_virtuals(class_ident);
+    let prefix = make_macro_prefix(prefix);
+    let selector = Helper::VTable.selector();
+    let mut slots = Vec::new();
+
+    // collect all generic args into descriptors
+    let def_generic_arg_idents = class
+        .vtable_generic_args()
+        .args
+        .iter()
+        .enumerate()
+        .map(|(idx, _)| format_ident!("def_generic_{idx}"))
+        .collect_vec();
+    let def_generic_matchers = make_generic_matchers(class);
+
+    // the VTable is named through the class, which is in scope wherever its derived classes are.
+    // its lifetimes are inferred, since the caller's predicates may only hold for its own lifetimes
+    let mut def_generic_args = def_generic_arg_idents.iter();
+    let class_args = class.generics.params.iter().map(|param| match param {
+        GenericParam::Lifetime(_) => quote!('_),
+        _ => {
+            let ident = def_generic_args.next().unwrap();
+            quote!($#ident)
+        }
+    });
+    let vtable_ty = quote!(<#prefix #class_ident <#(#class_args),*> as ::vtable_gen::HasVTable>::VTable);
+
+    if let Some((high_idx, _)) = virtuals.last_key_value() {
+        for idx in 0..=*high_idx {
+            // either translate the virtual into a function, or generate an unimplemented virtual
+            let expr = if let Some(virt) = virtuals.get(&idx) {
+                let ident = &virt.sig.ident;
+                if let Some(policy) = policy {
+                    let shim_ident = make_shim_ident(ident);
+                    quote!(<#vtable_ty>::#shim_ident::<$implementor_ty, #policy>)
+                } else {
+                    quote!(<$implementor_ty as #prefix #virtuals_ident <#($#def_generic_arg_idents),*>>::#ident)
+                }
+            } else {
+                quote!(|| ::core::unimplemented!())
+            };
+
+            slots.push(expr);
+        }
+    }
+
+    // generate the base vtable
+    if let Some(base_path) = class.bases.path(0) {
+        let macro_path = qualify_path(&prefix, &make_base_macro_path(base_path));
+        let base_module = qualify_base_module(&prefix, base_path);
+
+        // determine the position of each and extract it out of the parent definition
+        let base_def_args = extract_implementor_generics(class, base_path);
+        slots.insert(
+            0,
+            quote!(#macro_path!(#selector [#base_module] $implementor_ty, <#(#base_def_args),*>)),
+        )
+    }
+
+    let body = replace_crate_paths(quote! {
+        <#vtable_ty>::_new(#(#slots),*)
+    });
+
+    quote! {
+        // module: The path of the module the class is declared in, relative to the caller.
+        // implementor_ty: The type of the implementor.
+        // gen_x: The definition generic at position `x`.
+        (#selector [$($module:tt)*] $implementor_ty:ty, <#(#def_generic_matchers),*>) => {
+            #body
+        };
+    }
+}
+
+/// Make the identifier of the shim that guards a virtual against panics.
+fn make_shim_ident(ident: &Ident) -> Ident {
+    format_ident!("__shim_{ident}")
+}
+
+/// Generates a shim for each virtual with the virtual's ABI that calls the implementation and
+/// hands any panic to a policy, so that it never unwinds into the caller. The destructor's shim is
+/// as unsafe to call as the destructor.
+fn gen_vtable_shims(
+    class: &ItemClass,
+    virtuals: &BTreeMap<usize, Virtual>,
+    destructor: Option<&Ident>,
+) -> ItemImpl {
+    let class_ident = &class.ident;
+    let class_name = LitStr::new(&class_ident.to_string(), class_ident.span());
+    let vtable_ident = make_vtable_ident(class_ident);
+    let virtuals_ident = make_virtuals(class_ident);
+    let generics = class.vtable_generics();
+    let generic_args = class.vtable_generic_args();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let shims = virtuals.values().map(|virt| {
+        let lifetimes = collect_lifetimes(&virt.sig);
+        let ident = &virt.sig.ident;
+        let shim_ident = make_shim_ident(ident);
+        let method_name = LitStr::new(&ident.to_string(), ident.span());
+        let abi = &virt.sig.abi;
+        let output = &virt.sig.output;
+        // the policy must handle the return type for any lifetime the caller chooses
+        let policy_bound = match output {
+            ReturnType::Default => quote!(::vtable_gen::PanicPolicy<()>),
+            ReturnType::Type(_, ty) => {
+                let mut ty = ty.as_ref().clone();
+                LifetimeEliser::new(lifetimes.clone()).visit_type_mut(&mut ty);
+                let mut namer = ElidedLifetimeNamer::new(parse_quote!('__elided));
+                namer.visit_type_mut(&mut ty);
+
+                let lifetime = &namer.lifetime;
+                let hrtb = namer.named.then(|| quote!(for<#lifetime>));
+                quote!(#hrtb ::vtable_gen::PanicPolicy<#ty>)
+            }
+        };
+
+        // name the arguments so that patterns in the signature don't matter
+        let (arg_idents, arg_tys): (Vec<_>, Vec<_>) = virt
+            .sig
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(idx, arg)| match arg {
+                FnArg::Typed(arg) => (format_ident!("arg_{idx}"), &arg.ty),
+                FnArg::Receiver(_) => {
+                    panic!("virtuals must be standardized before generating shims")
+                }
+            })
+            .unzip();
+
+        let call = quote!(<__Implementor as #virtuals_ident #generic_args>::#ident(#(#arg_idents),*));
+        let (unsafety, call) = if destructor == Some(ident) {
+            (Some(quote!(unsafe)), quote!(unsafe { #call }))
+        } else {
+            (None, call)
+        };
+
+        quote! {
+            #[doc(hidden)]
+            pub #unsafety #abi fn #shim_ident<
+                #(#lifetimes,)*
+                __Implementor: #virtuals_ident #generic_args,
+                __Policy: #policy_bound,
+            >(#(#arg_idents: #arg_tys),*) #output {
+                ::vtable_gen::guard::<__Policy, _>(#class_name, #method_name, move || {
+                    #call
+                })
+            }
+        }
+    });
+
+    parse_quote! {
+        impl #impl_generics #vtable_ident #ty_generics #where_clause {
+            #(#shims)*
+        }
+    }
+}
+
+/// Generates a VTable constant for the class or one of its secondary bases, along with a function
+/// that returns a unique static copy of it. Generic classes can't declare statics, so their
+/// copies live in a registry.
+fn gen_vtable_static_for(
+    class: &ItemClass,
+    secondary_base: Option<&Path>,
+) -> (ItemConst, ImplItemFn) {
+    let class_ident = &class.ident;
+    let class_generics = class.generic_args();
+    let vis = &class.vis;
+    let vtable_generics = class.vtable_generic_args();
+    let class_vtable_ident = make_vtable_ident(class_ident);
+    let selector = Helper::VTable.selector();
+
+    // secondary VTables are named through the base, and pass on its generic arguments
+    let (vtable_ty, base_generics, vtable_struct_ty, macro_path, base_module) =
+        if let Some(base_path) = secondary_base {
+            let vtable_struct_ty = make_base_vtable_ty(base_path);
+            let base_path = strip_lifetimes(base_path);
+            let last_segment = last_segment(&base_path);
+            let base_generics =
+                if let PathArguments::AngleBracketed(def_generics) = &last_segment.arguments {
+                    def_generics.clone()
+                } else {
+                    parse_quote!(<>)
+                };
+            let macro_path = make_base_macro_path(&base_path);
+
+            (
+                last_segment.ident.clone(),
+                base_generics,
+                vtable_struct_ty,
+                macro_path,
+                make_base_module(&base_path),
+            )
+        } else {
+            (
+                class_ident.clone(),
+                vtable_generics.clone(),
+                parse_quote!(#class_vtable_ident #vtable_generics),
+                class_ident.clone().into(),
+                TokenStream::new(),
+            )
+        };
+
+    // statics can't name the lifetimes of their impl, and the VTable is the same for all of them
+    let vtable_static_path = make_vtable_static(
+        class_ident,
+        &vtable_ty,
+        &class.generic_args_with_lifetime(&parse_quote!('static)),
+    );
+    let vtable_static_ident = extract_ident(&vtable_static_path);
+    let vtable_accessor_ident = extract_ident(&make_vtable_accessor(
+        class_ident,
+        &vtable_ty,
+        &class_generics,
+    ))
+    .clone();
+
+    // each use of the constant is a new copy of the VTable, so only the accessors are public API
+    let output = quote! {
+        #[doc(hidden)]
+        #vis const #vtable_static_ident: #vtable_struct_ty =
+            #macro_path!(#selector [#base_module] #class_ident #class_generics, #base_generics);
+    };
+    let constant = syn::parse(output.into())
+        .unwrap_or_else(|e| panic!("failed to generate vtable {vtable_ty} for {class_ident}: {e}"));
+
+    let accessor = if vtable_generics.args.is_empty() {
+        parse_quote! {
+            /// Returns the unique static instance of the VTable.
+            #vis const fn #vtable_accessor_ident() -> &'static #vtable_struct_ty {
+                static VTBL: #vtable_struct_ty = #vtable_static_path;
+                &VTBL
+            }
+        }
+    } else {
+        parse_quote! {
+            /// Returns the unique registered instance of the VTable.
+            #vis fn #vtable_accessor_ident() -> &'static #vtable_struct_ty {
+                static REGISTRY: ::vtable_gen::VTableRegistry = ::vtable_gen::VTableRegistry::new();
+                REGISTRY.get_or_insert::<#class_vtable_ident #vtable_generics, #vtable_struct_ty>(
+                    || Self::#vtable_static_ident
+                )
+            }
+        }
+    };
+
+    (constant, accessor)
+}
+
+/// Generates the default VTable for the class.
+fn gen_vtable_static(
+    class: &ItemClass,
+    additional_bases: &HashMap<Path, Vec<Path>>,
+    destruct: Option<&ImplItemFn>,
+) -> File {
+    let class_ident = &class.ident;
+    // the vtables of generic classes are registered by `TypeId`, which needs `'static` parameters
+    let generics = class.static_generics();
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let generic_args = class.generic_args();
+    let vtable_generic_args = class.vtable_generic_args();
+
+    // generate the primary vtable, followed by the secondary vtables
+    let (consts, accessors): (Vec<_>, Vec<_>) = iter::once(None)
+        .chain(
+            collect_secondary_bases(class, additional_bases)
+                .into_iter()
+                .map(Some),
+        )
+        .map(|secondary_base| gen_vtable_static_for(class, secondary_base))
+        .unzip();
+
+    let vtable_ident = make_vtable_ident(class_ident);
+    let primary_accessor = &accessors[0].sig.ident;
+    let output = quote! {
+        impl #impl_generics #class_ident #generic_args #where_clause {
+            #(#consts)*
+            #(#accessors)*
+        }
+
+        impl #impl_generics ::vtable_gen::CppClass for #class_ident #generic_args #where_clause {
+            type VTable = #vtable_ident #vtable_generic_args;
+
+            fn vtable() -> &'static Self::VTable {
+                Self::#primary_accessor()
+            }
+
+            #destruct
+        }
+    };
+    syn::parse(output.into()).expect("failed to generate vtable static")
+}
+
+/// Generates the VTable struct for the class.
+fn gen_vtable_struct(class: &ItemClass, virtuals: &BTreeMap<usize, Virtual>) -> File {
+    let vis = &class.vis;
+    let vtable_ident = make_vtable_ident(&class.ident);
+    let mut fields = Punctuated::<Field, Comma>::new();
+
+    if let Some((high_idx, _)) = virtuals.last_key_value() {
+        for idx in 0..=*high_idx {
+            // either translate the virtual into a function, or generate an unimplemented virtual
+            let (virt_ident, virt_ty, attrs) = if let Some(virt) = virtuals.get(&idx) {
+                let ident = virt.sig.ident.clone();
+
+                let unsafety = &virt.sig.unsafety;
+                let abi = virt.sig.abi.clone().unwrap();
+                let args = virt.sig.inputs.clone();
+                let output = &virt.sig.output;
+
+                // we need to generate the type from the signature. the slot is shared by
+                // instances of any lifetime
+                let lifetimes = collect_lifetimes(&virt.sig);
+                let hrtb = (!lifetimes.is_empty()).then(|| quote!(for<#(#lifetimes),*>));
+                let ty = parse_quote!(#hrtb #unsafety #abi fn(#args) #output);
+
+                (ident, ty, virt.attrs.clone())
+            } else {
+                let ident = format_ident!("unimpl_{idx}");
+                let ty = parse_quote!(fn());
+                (ident, ty, vec![])
+            };
+
+            fields.push(Field {
+                attrs,
+                vis: parse_quote!(pub),
+                mutability: FieldMutability::None,
+                ident: Some(virt_ident),
+                colon_token: None,
+                ty: virt_ty,
+            });
+        }
+    }
+
+    // add the base VTable if there is one
+    if let Some(base_path) = class.bases.path(0) {
+        let base_vtable_ty = make_base_vtable_ty(base_path);
+
+        fields.insert(
+            0,
+            Field {
+                attrs: vec![],
+                vis: parse_quote!(pub),
+                mutability: FieldMutability::None,
+                ident: Some(make_base_name(extract_ident(base_path))),
+                colon_token: None,
+                ty: base_vtable_ty,
+            },
+        )
+    }
+
+    let generics = class.vtable_generics();
+    if !generics.params.is_empty() {
+        let args = generics
+            .params
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericParam::Type(ty) => Some(&ty.ident),
+                _ => None,
+            })
+            .collect_vec();
+        fields.push(parse_quote!(pub _marker: ::core::marker::PhantomData <(#(#args),*)>))
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // the base is compared as a whole, and each slot by the address of its function
+    let base_ident = class
+        .bases
+        .path(0)
+        .map(|base_path| make_base_name(extract_ident(base_path)));
+    let base_ident = base_ident.iter().collect_vec();
+    let slot_idents = fields
+        .iter()
+        .filter_map(|field| field.ident.as_ref())
+        .filter(|ident| !base_ident.contains(ident) && *ident != "_marker")
+        .collect_vec();
+    let slot_names = slot_idents
+        .iter()
+        .map(|ident| LitStr::new(&ident.to_string(), ident.span()))
+        .collect_vec();
+    let class_name = LitStr::new(&class.ident.to_string(), class.ident.span());
+
+    // the VTable is built in order by derived classes, which may not be able to name its fields
+    let (field_idents, arg_tys): (Vec<_>, Vec<_>) = fields
+        .iter()
+        .filter_map(|field| field.ident.as_ref().map(|ident| (ident, &field.ty)))
+        .filter(|(ident, _)| *ident != "_marker")
+        .unzip();
+    let arg_idents = (0..field_idents.len())
+        .map(|idx| format_ident!("arg_{idx}"))
+        .collect_vec();
+    let marker = (!generics.params.is_empty()).then(|| quote!(_marker: ::core::marker::PhantomData));
+
+    // the class names its VTable for any lifetime
+    let class_ident = &class.ident;
+    let class_generic_args = class.generic_args();
+    let mut class_generics = generics.clone();
+    class_generics.params = class
+        .lifetimes()
+        .into_iter()
+        .map(|lt| GenericParam::Lifetime(LifetimeParam::new(lt)))
+        .chain(class_generics.params)
+        .collect();
+    let (class_impl_generics, _, _) = class_generics.split_for_impl();
+
+    let output = quote! {
+        #[repr(C)]
+        #vis struct #vtable_ident #generics #where_clause {
+            #fields
+        }
+
+        impl #impl_generics #vtable_ident #ty_generics #where_clause {
+            /// Makes the VTable out of its base VTable and its slots, in order.
+            #[doc(hidden)]
+            pub const fn _new(#(#arg_idents: #arg_tys),*) -> Self {
+                Self {
+                    #(#field_idents: #arg_idents,)*
+                    #marker
+                }
+            }
+        }
+
+        impl #class_impl_generics ::vtable_gen::HasVTable for #class_ident #class_generic_args #where_clause {
+            type VTable = #vtable_ident #ty_generics;
+        }
+
+        impl #impl_generics ::core::cmp::PartialEq for #vtable_ident #ty_generics #where_clause {
+            fn eq(&self, other: &Self) -> bool {
+                true #(&& self.#base_ident == other.#base_ident)*
+                    #(&& self.#slot_idents as usize == other.#slot_idents as usize)*
+            }
+        }
+
+        impl #impl_generics ::core::cmp::Eq for #vtable_ident #ty_generics #where_clause {}
+
+        impl #impl_generics ::core::hash::Hash for #vtable_ident #ty_generics #where_clause {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                #(::core::hash::Hash::hash(&self.#base_ident, state);)*
+                #(::core::hash::Hash::hash(&(self.#slot_idents as usize), state);)*
+            }
+        }
+
+        impl #impl_generics ::core::fmt::Debug for #vtable_ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_struct(#class_name)
+                    #(.field(stringify!(#base_ident), &self.#base_ident))*
+                    #(.field(#slot_names, &::vtable_gen::DebugSlot(self.#slot_idents as *const ())))*
+                    .finish()
+            }
+        }
+    };
+    syn::parse(output.into()).expect("failed to generate vtable struct")
+}
+
+/// Organizes the virtuals in index-order.
+pub fn sort_virtuals(class: &ItemClass) -> BTreeMap<usize, Virtual> {
+    let mut virtuals = BTreeMap::new();
+    let mut last_idx = None;
+    for virt in class.body.virtuals.iter() {
+        let idx = match (&virt.index.idx, &last_idx) {
+            (Some(idx), _) => idx.base10_parse().expect("virtual index must be base-10"),
+            (None, Some(last_idx)) => *last_idx + 1,
+            (None, None) => 0,
+        };
+
+        // try to insert the virtual
+        if let Some(last_virt) = virtuals.insert(idx, virt.clone()) {
+            panic!(
+                "virtual {} already occupies index {idx}",
+                last_virt.sig.ident
+            );
+        }
+
+        last_idx = Some(idx);
+    }
+
+    virtuals
+}
@@ -0,0 +1,25 @@
+use syn::{Attribute, Expr, ExprLit, Lit, Path};
+
+use crate::class::extractor::AttributeExtractor;
+
+/// The path of the module the class is declared in, used to name the class's items from its
+/// helper macros.
+pub struct VTablePath;
+
+impl AttributeExtractor for VTablePath {
+    type Output = Path;
+
+    fn attr() -> &'static str {
+        "vtable_path"
+    }
+
+    fn parse_attr(attr: Attribute) -> syn::Result<Self::Output> {
+        match &attr.meta.require_name_value()?.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(path),
+                ..
+            }) => path.parse(),
+            value => Err(syn::Error::new_spanned(value, "expected a string path")),
+        }
+    }
+}
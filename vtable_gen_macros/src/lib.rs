@@ -0,0 +1,13 @@
+//! Procedural macros for `vtable_gen`. Use them through the `vtable_gen` crate.
+
+use proc_macro::TokenStream;
+
+mod class;
+mod parse;
+mod util;
+
+/// Generates a VTable member for a struct, or a trait if the struct is a vtable. See examples.
+#[proc_macro]
+pub fn cpp_class(input: TokenStream) -> TokenStream {
+    class::cpp_class_impl(input)
+}
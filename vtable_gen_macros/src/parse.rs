@@ -0,0 +1,486 @@
+use std::mem;
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{
+    AngleBracketedGenericArguments, Attribute, braced, Expr, Field, GenericArgument, GenericParam,
+    Generics, ImplItem, ImplItemConst, ImplItemFn, ItemImpl, Lifetime, LitInt, parenthesized,
+    parse_quote, Path, Signature, token, Token, Type, TypeParamBound, Visibility, WhereClause,
+    WherePredicate,
+};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::visit::Visit;
+
+use crate::util::{last_segment, LifetimeFinder, remove_punctuated};
+
+/// Base classes.
+#[derive(Debug, Default, Clone)]
+pub struct BaseClasses {
+    pub colon_token: Option<Token![:]>,
+    pub bases: Vec<(Path, Option<Token![,]>)>,
+}
+
+impl BaseClasses {
+    /// Return the identifier of the base at `index`.
+    pub fn ident(&self, index: usize) -> Option<&Ident> {
+        self.path(index).map(|path| &last_segment(path).ident)
+    }
+
+    /// Returns true if there are no base classes.
+    pub fn is_empty(&self) -> bool {
+        self.bases.is_empty()
+    }
+
+    /// Return the path of the base at `index`.
+    pub fn path(&self, index: usize) -> Option<&Path> {
+        self.bases.get(index).map(|(path, _)| path)
+    }
+
+    /// Returns an iterator over all paths.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.bases.iter().map(|(path, _)| path)
+    }
+}
+
+impl Parse for BaseClasses {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if !input.peek(Token![:]) {
+            return Ok(BaseClasses::default());
+        }
+
+        let colon_token = input.parse()?;
+        let mut bases = Vec::new();
+        // keep parsing types until we hit the open brace
+        loop {
+            if input.is_empty() {
+                break;
+            }
+
+            let ty = input.parse()?;
+            let comma_token = input.parse()?;
+            bases.push((ty, comma_token));
+
+            if input.peek(token::Brace) || input.peek(Token![where]) {
+                break;
+            }
+        }
+
+        Ok(Self { colon_token, bases })
+    }
+}
+
+/// The body of a class. Fields, virtuals and non-virtual items may be declared in any order.
+#[derive(Debug, Clone)]
+pub struct ClassBody {
+    braces: token::Brace,
+    pub fields: Punctuated<Field, Token![,]>,
+    pub virtuals: Punctuated<Virtual, Token![,]>,
+    /// The associated constants and non-virtual functions, which become inherent items.
+    pub items: Vec<ImplItem>,
+}
+
+impl Parse for ClassBody {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        let braces = braced!(content in input);
+
+        let mut fields = Punctuated::new();
+        let mut virtuals = Punctuated::new();
+        let mut items = Vec::new();
+        while !content.is_empty() {
+            let attrs = content.call(Attribute::parse_outer)?;
+
+            // attributes may come before or after `virtual`
+            if content.peek(Token![virtual]) {
+                let mut virt: Virtual = content.parse()?;
+                virt.attrs.splice(0..0, attrs);
+                virtuals.push(virt);
+                if let Some(comma_token) = content.parse()? {
+                    virtuals.push_punct(comma_token);
+                }
+                continue;
+            }
+
+            let ahead = content.fork();
+            ahead.parse::<Visibility>()?;
+            if ahead.peek(syn::Ident) && ahead.peek2(Token![:]) {
+                let mut field = content.call(Field::parse_named)?;
+                field.attrs = attrs;
+                fields.push(field);
+                if let Some(comma_token) = content.parse()? {
+                    fields.push_punct(comma_token);
+                }
+            } else if ahead.peek(Token![static]) {
+                items.push(ImplItem::Const(parse_static(&content, attrs)?));
+                content.parse::<Option<Token![,]>>()?;
+            } else {
+                let mut func: ImplItemFn = content.parse()?;
+                func.attrs.splice(0..0, attrs);
+                items.push(ImplItem::Fn(func));
+            }
+        }
+
+        Ok(Self {
+            braces,
+            fields,
+            virtuals,
+            items,
+        })
+    }
+}
+
+/// Parses a `static` member, which becomes an associated constant.
+fn parse_static(input: ParseStream, attrs: Vec<Attribute>) -> syn::Result<ImplItemConst> {
+    let vis: Visibility = input.parse()?;
+    let static_token: Token![static] = input.parse()?;
+    if input.peek(Token![mut]) {
+        return Err(input.error("`static` members can't be mutable"));
+    }
+    let ident: Ident = input.parse()?;
+    input.parse::<Token![:]>()?;
+    let ty: Type = input.parse()?;
+    input.parse::<Token![=]>()?;
+    let expr: Expr = input.parse()?;
+
+    let const_token = Token![const](static_token.span);
+    Ok(parse_quote! {
+        #(#attrs)*
+        #vis #const_token #ident: #ty = #expr;
+    })
+}
+
+/// A total class definition.
+#[derive(Debug, Clone)]
+pub struct ItemClass {
+    pub attrs: Vec<Attribute>,
+    pub vis: Visibility,
+    pub struct_token: Token![struct],
+    pub ident: Ident,
+    pub generics: Generics,
+    pub bases: BaseClasses,
+    pub body: ClassBody,
+}
+
+impl ItemClass {
+    /// Returns the generic arguments used.
+    pub fn generic_args(&self) -> AngleBracketedGenericArguments {
+        let mut args = Punctuated::new();
+        for param in self.generics.params.iter() {
+            args.push(match param {
+                GenericParam::Type(ty) => {
+                    let ident = &ty.ident;
+                    parse_quote!(#ident)
+                }
+                GenericParam::Lifetime(lt) => GenericArgument::Lifetime(lt.lifetime.clone()),
+                GenericParam::Const(ct) => {
+                    let ident = &ct.ident;
+                    parse_quote!(#ident)
+                }
+            })
+        }
+
+        AngleBracketedGenericArguments {
+            colon2_token: None,
+            lt_token: parse_quote!(<),
+            args,
+            gt_token: parse_quote!(>),
+        }
+    }
+
+    /// Returns the generic arguments used, with each lifetime replaced by `lifetime`.
+    pub fn generic_args_with_lifetime(
+        &self,
+        lifetime: &Lifetime,
+    ) -> AngleBracketedGenericArguments {
+        let mut generic_args = self.generic_args();
+        for arg in generic_args.args.iter_mut() {
+            if let GenericArgument::Lifetime(lt) = arg {
+                *lt = lifetime.clone();
+            }
+        }
+        generic_args
+    }
+
+    /// Returns the generic parameters of the VTable and virtuals trait. These leave out the
+    /// class's lifetimes, along with any bounds and predicates that name them, since a VTable is
+    /// shared by instances of any lifetime.
+    pub fn vtable_generics(&self) -> Generics {
+        let lifetimes = self.lifetimes();
+        let is_class_lifetime = |bound: &TypeParamBound| matches!(bound, TypeParamBound::Lifetime(lt) if lifetimes.contains(lt));
+
+        let mut generics = self.generics.clone();
+        generics.params = generics
+            .params
+            .into_iter()
+            .filter(|param| !matches!(param, GenericParam::Lifetime(_)))
+            .map(|mut param| {
+                if let GenericParam::Type(ty) = &mut param {
+                    ty.bounds = remove_punctuated(&ty.bounds, |bound| !is_class_lifetime(bound));
+                    if ty.bounds.is_empty() {
+                        ty.colon_token = None;
+                    }
+                }
+                param
+            })
+            .collect();
+
+        if let Some(where_clause) = &mut generics.where_clause {
+            where_clause.predicates = where_clause
+                .predicates
+                .iter()
+                .filter_map(|predicate| match predicate {
+                    WherePredicate::Type(predicate) => {
+                        let mut predicate = predicate.clone();
+                        predicate.bounds =
+                            remove_punctuated(&predicate.bounds, |bound| !is_class_lifetime(bound));
+
+                        // predicates that still name the class's lifetimes can't be kept
+                        let mut finder = LifetimeFinder::new(lifetimes.clone());
+                        finder.visit_predicate_type(&predicate);
+                        (!predicate.bounds.is_empty() && !finder.found)
+                            .then_some(WherePredicate::Type(predicate))
+                    }
+                    _ => None,
+                })
+                .collect();
+        }
+        generics
+    }
+
+    /// Returns the generic arguments of the VTable and virtuals trait.
+    pub fn vtable_generic_args(&self) -> AngleBracketedGenericArguments {
+        let mut generic_args = self.generic_args();
+        generic_args.args = generic_args
+            .args
+            .into_iter()
+            .filter(|arg| !matches!(arg, GenericArgument::Lifetime(_)))
+            .collect();
+        generic_args
+    }
+
+    /// Returns the predicates that bound each type parameter to be `'static`, which looking up the
+    /// class's vtables requires.
+    pub fn static_predicates(&self) -> Vec<WherePredicate> {
+        self.generics
+            .type_params()
+            .map(|ty| {
+                let ident = &ty.ident;
+                parse_quote!(#ident: 'static)
+            })
+            .collect()
+    }
+
+    /// Returns the class's generics, with each type parameter bound to be `'static`.
+    pub fn static_generics(&self) -> Generics {
+        let mut generics = self.generics.clone();
+        generics
+            .make_where_clause()
+            .predicates
+            .extend(self.static_predicates());
+        generics
+    }
+
+    /// Returns the class's lifetime parameters.
+    pub fn lifetimes(&self) -> Vec<Lifetime> {
+        self.generics
+            .lifetimes()
+            .map(|lt| lt.lifetime.clone())
+            .collect()
+    }
+}
+
+impl Parse for ItemClass {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let struct_token = input.parse()?;
+        let ident = input.parse()?;
+        let mut generics: Generics = input.parse()?;
+
+        // the where clause may come before or after the bases
+        generics.where_clause = input.parse()?;
+        let bases = input.parse()?;
+        if let Some(where_clause) = input.parse::<Option<WhereClause>>()? {
+            generics
+                .make_where_clause()
+                .predicates
+                .extend(where_clause.predicates);
+        }
+
+        Ok(Self {
+            attrs,
+            vis,
+            struct_token,
+            ident,
+            generics,
+            bases,
+            body: input.parse()?,
+        })
+    }
+}
+
+/// A C++ Definition, containing a class and its implementations.
+#[derive(Debug, Clone)]
+pub struct CppDef {
+    pub class: ItemClass,
+    pub impls: Vec<ItemImpl>,
+}
+
+impl Parse for CppDef {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut class: ItemClass = input.parse()?;
+        let mut impls = Vec::new();
+
+        // the class's non-virtual items are implemented like any other
+        let items = mem::take(&mut class.body.items);
+        if !items.is_empty() {
+            let ident = &class.ident;
+            let generic_args = class.generic_args();
+            let (impl_generics, _, where_clause) = class.generics.split_for_impl();
+            impls.push(parse_quote! {
+                impl #impl_generics #ident #generic_args #where_clause {
+                    #(#items)*
+                }
+            });
+        }
+
+        while !input.is_empty() {
+            impls.push(input.parse()?);
+        }
+
+        Ok(Self { class, impls })
+    }
+}
+
+/// Virtual functions.
+#[derive(Debug, Clone)]
+pub struct Virtual {
+    pub virtual_token: Token![virtual],
+    pub index: VirtualIndex,
+    pub attrs: Vec<Attribute>,
+    pub vis: Visibility,
+    pub sig: Signature,
+}
+
+impl Parse for Virtual {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            virtual_token: input.parse()?,
+            index: input.parse()?,
+            attrs: input.call(Attribute::parse_outer)?,
+            vis: input.parse()?,
+            sig: input.parse()?,
+        })
+    }
+}
+
+/// The index of a virtual method.
+#[derive(Debug, Default, Clone)]
+pub struct VirtualIndex {
+    pub paren_token: Option<token::Paren>,
+    pub idx: Option<LitInt>,
+}
+
+impl Parse for VirtualIndex {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if !input.peek(token::Paren) {
+            return Ok(Self::default());
+        }
+
+        let content;
+        Ok(Self {
+            paren_token: Some(parenthesized!(content in input)),
+            idx: Some(content.parse()?),
+        })
+    }
+}
+
+impl ToTokens for BaseClasses {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if let Some(colon_token) = self.colon_token {
+            colon_token.to_tokens(tokens);
+            for (ty, comma_token) in &self.bases {
+                ty.to_tokens(tokens);
+                comma_token.to_tokens(tokens);
+            }
+        }
+    }
+}
+
+impl ToTokens for ClassBody {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.braces.surround(tokens, |tokens| {
+            for pair in self.fields.pairs() {
+                pair.value().to_tokens(tokens);
+                pair.punct().to_tokens(tokens);
+            }
+
+            for pair in self.virtuals.pairs() {
+                pair.value().to_tokens(tokens);
+                pair.punct().to_tokens(tokens);
+            }
+
+            for item in &self.items {
+                match item {
+                    ImplItem::Const(item) => {
+                        let attrs = &item.attrs;
+                        let vis = &item.vis;
+                        let ident = &item.ident;
+                        let ty = &item.ty;
+                        let expr = &item.expr;
+                        tokens.extend(quote!(#(#attrs)* #vis static #ident: #ty = #expr,));
+                    }
+                    item => item.to_tokens(tokens),
+                }
+            }
+        })
+    }
+}
+
+impl ToTokens for CppDef {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.class.to_tokens(tokens);
+        for imp in &self.impls {
+            imp.to_tokens(tokens);
+        }
+    }
+}
+
+impl ToTokens for ItemClass {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for attr in &self.attrs {
+            attr.to_tokens(tokens);
+        }
+
+        self.vis.to_tokens(tokens);
+        self.struct_token.to_tokens(tokens);
+        self.ident.to_tokens(tokens);
+        self.generics.to_tokens(tokens);
+        self.bases.to_tokens(tokens);
+        self.generics.where_clause.to_tokens(tokens);
+        self.body.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Virtual {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.virtual_token.to_tokens(tokens);
+        self.index.to_tokens(tokens);
+
+        for attr in &self.attrs {
+            attr.to_tokens(tokens);
+        }
+
+        self.vis.to_tokens(tokens);
+        self.sig.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for VirtualIndex {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if let Some(paren_token) = self.paren_token.as_ref() {
+            paren_token.surround(tokens, |tokens| self.idx.to_tokens(tokens))
+        }
+    }
+}
@@ -0,0 +1,370 @@
+use std::collections::HashMap;
+use std::iter;
+
+use itertools::Itertools;
+use proc_macro2::{Group, Ident, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    GenericArgument, GenericParam, Lifetime, parse_quote, Path, PathArguments, PathSegment,
+    Signature, Type, TypeReference,
+};
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::visit_mut::{self, VisitMut};
+
+use crate::parse::ItemClass;
+
+/// Collects secondary base classes.
+pub fn collect_secondary_bases<'a>(
+    class: &'a ItemClass,
+    additional_bases: &'a HashMap<Path, Vec<Path>>,
+) -> Vec<&'a Path> {
+    class
+        .bases
+        .paths()
+        .flat_map(|path| {
+            iter::once(path)
+                .chain(
+                    additional_bases
+                        .get(path)
+                        .iter()
+                        .flat_map(|paths| paths.iter()),
+                )
+                .collect_vec()
+        })
+        .skip(1)
+        .collect()
+}
+
+/// Extracts an identifier out of the end of a path.
+pub fn extract_ident(path: &Path) -> &Ident {
+    &last_segment(path).ident
+}
+
+/// Makes the type of the primary VTable of a base class. It is named through the base, so that it
+/// resolves wherever the base does, and the VTable is the same for instances of any lifetime.
+pub fn make_base_vtable_ty(base_path: &Path) -> Type {
+    let mut path = base_path.clone();
+    if let PathArguments::AngleBracketed(args) = &mut last_segment_mut(&mut path).arguments {
+        for arg in args.args.iter_mut() {
+            if let GenericArgument::Lifetime(lt) = arg {
+                *lt = parse_quote!('static);
+            }
+        }
+    }
+    parse_quote!(<#path as ::vtable_gen::HasVTable>::VTable)
+}
+
+/// Removes the lifetime arguments from the last segment of a path.
+pub fn strip_lifetimes(path: &Path) -> Path {
+    let mut path = path.clone();
+    if let PathArguments::AngleBracketed(args) = &mut last_segment_mut(&mut path).arguments {
+        args.args = remove_punctuated(&args.args, |arg| {
+            !matches!(arg, GenericArgument::Lifetime(_))
+        });
+    }
+    path
+}
+
+/// Replaces each of the given lifetimes with an elided lifetime, so that signatures and types can
+/// be used outside of the items that declare the lifetimes.
+pub struct LifetimeEliser(Vec<Lifetime>);
+
+impl LifetimeEliser {
+    pub fn new(lifetimes: Vec<Lifetime>) -> Self {
+        Self(lifetimes)
+    }
+}
+
+impl VisitMut for LifetimeEliser {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if self.0.contains(lifetime) {
+            *lifetime = parse_quote!('_);
+        }
+    }
+}
+
+/// Finds whether any of the given lifetimes are named.
+pub struct LifetimeFinder {
+    lifetimes: Vec<Lifetime>,
+    pub found: bool,
+}
+
+impl LifetimeFinder {
+    pub fn new(lifetimes: Vec<Lifetime>) -> Self {
+        Self {
+            lifetimes,
+            found: false,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for LifetimeFinder {
+    fn visit_lifetime(&mut self, lifetime: &'ast Lifetime) {
+        if self.lifetimes.contains(lifetime) {
+            self.found = true;
+        }
+        visit::visit_lifetime(self, lifetime);
+    }
+}
+
+/// Names every elided lifetime in a type.
+pub struct ElidedLifetimeNamer {
+    pub lifetime: Lifetime,
+    pub named: bool,
+}
+
+impl ElidedLifetimeNamer {
+    pub fn new(lifetime: Lifetime) -> Self {
+        Self {
+            lifetime,
+            named: false,
+        }
+    }
+}
+
+impl VisitMut for ElidedLifetimeNamer {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.lifetime.clone();
+            self.named = true;
+        }
+    }
+
+    fn visit_type_reference_mut(&mut self, reference: &mut TypeReference) {
+        if reference.lifetime.is_none() {
+            reference.lifetime = Some(self.lifetime.clone());
+            self.named = true;
+        }
+        visit_mut::visit_type_reference_mut(self, reference);
+    }
+}
+
+/// Collects the lifetimes declared by a signature.
+pub fn collect_lifetimes(sig: &Signature) -> Vec<Lifetime> {
+    sig.generics
+        .lifetimes()
+        .map(|lt| lt.lifetime.clone())
+        .collect()
+}
+
+/// Makes the path of the helper macro of a base class, which is named like the base.
+pub fn make_base_macro_path(base_path: &Path) -> Path {
+    let mut path = base_path.clone();
+    last_segment_mut(&mut path).arguments = PathArguments::None;
+    path
+}
+
+/// Makes the prefix that names the class's items from inside its helper macros. This is the
+/// class's `vtable_path` if it has one, and otherwise the module path passed in by the caller.
+pub fn make_macro_prefix(prefix: &TokenStream) -> TokenStream {
+    if prefix.is_empty() {
+        quote!($($module)*)
+    } else {
+        prefix.clone()
+    }
+}
+
+/// Makes the path of the module a base class lives in, relative to the module of the class.
+pub fn make_base_module(base_path: &Path) -> TokenStream {
+    let leading_colon = &base_path.leading_colon;
+    let modules = base_path
+        .segments
+        .iter()
+        .take(base_path.segments.len() - 1)
+        .map(|segment| &segment.ident);
+    quote!(#leading_colon #(#modules::)*)
+}
+
+/// Qualifies a path relative to the class's module with the class's macro prefix, so that it can
+/// be emitted inside the class's helper macros.
+pub fn qualify_path(prefix: &TokenStream, path: &Path) -> TokenStream {
+    if is_absolute(path) {
+        path.to_token_stream()
+    } else {
+        quote!(#prefix #path)
+    }
+}
+
+/// Qualifies the module of a base class with the class's macro prefix, so that it can be passed
+/// on from inside the class's helper macros.
+pub fn qualify_base_module(prefix: &TokenStream, base_path: &Path) -> TokenStream {
+    let base_module = make_base_module(base_path);
+    if is_absolute(base_path) {
+        base_module
+    } else {
+        quote!(#prefix #base_module)
+    }
+}
+
+/// Returns true if the path doesn't depend on the module it is written in.
+fn is_absolute(path: &Path) -> bool {
+    path.leading_colon.is_some()
+        || path
+            .segments
+            .first()
+            .is_some_and(|segment| segment.ident == "crate" || segment.ident == "$crate")
+}
+
+/// Extracts generics from an implementor.
+pub fn extract_implementor_generics(class: &ItemClass, base_path: &Path) -> Vec<TokenStream> {
+    let class_generics = class.vtable_generic_args();
+
+    // extract the angle bracketed_arguments
+    let def_generics = match &base_path
+        .segments
+        .last()
+        .expect("expected path segment")
+        .arguments
+    {
+        PathArguments::AngleBracketed(def_generics) => def_generics.clone(),
+        _ => parse_quote!(<>),
+    };
+
+    // determine the position of each and extract it out of the parent definition
+    def_generics
+        .args
+        .iter()
+        .filter(|base_arg| !matches!(base_arg, GenericArgument::Lifetime(_)))
+        .map(|base_arg| {
+            class_generics
+                .args
+                .iter()
+                .position(|class_arg| class_arg == base_arg)
+                .map(|idx| {
+                    let ident = format_ident!("def_generic_{idx}");
+                    quote! { $#ident }
+                })
+                // the argument may use the class's generics, as in `Vec<T>` or `{ N }`
+                .unwrap_or_else(|| {
+                    replace_generics_with_descriptors(class, base_arg.to_token_stream())
+                })
+        })
+        .collect()
+}
+
+/// Makes the macro matchers of the class's `def_generic_x` descriptors. Types are matched whole,
+/// since they can span many tokens, like `Vec<T>`.
+pub fn make_generic_matchers(class: &ItemClass) -> Vec<TokenStream> {
+    class
+        .vtable_generics()
+        .params
+        .iter()
+        .enumerate()
+        .map(|(idx, param)| {
+            let ident = format_ident!("def_generic_{idx}");
+            match param {
+                GenericParam::Type(_) => quote!($#ident: ty),
+                _ => quote!($#ident: tt),
+            }
+        })
+        .collect()
+}
+
+/// Replaces each of the class's generic parameters in `tokens` with its `def_generic_x` macro
+/// descriptor, so the tokens can be emitted inside the class's helper macros.
+pub fn replace_generics_with_descriptors(class: &ItemClass, tokens: TokenStream) -> TokenStream {
+    let generic_args = class.vtable_generic_args();
+    let descriptors = generic_args
+        .args
+        .iter()
+        .enumerate()
+        .map(|(idx, arg)| {
+            let ident = format_ident!("def_generic_{idx}");
+            (arg.to_token_stream().to_string(), quote!($#ident))
+        })
+        .collect::<HashMap<_, _>>();
+
+    replace_idents(tokens, &descriptors)
+}
+
+fn replace_idents(tokens: TokenStream, replacements: &HashMap<String, TokenStream>) -> TokenStream {
+    tokens
+        .into_iter()
+        .flat_map(|token| match token {
+            TokenTree::Ident(ident) => replacements
+                .get(&ident.to_string())
+                .cloned()
+                .unwrap_or_else(|| ident.into_token_stream()),
+            TokenTree::Group(group) => {
+                let mut new_group = Group::new(
+                    group.delimiter(),
+                    replace_idents(group.stream(), replacements),
+                );
+                new_group.set_span(group.span());
+                new_group.into_token_stream()
+            }
+            token => token.into_token_stream(),
+        })
+        .collect()
+}
+
+/// Borrows the fields that lead from `this`, a reference to `Self`, to one of its bases through a
+/// pointer to the whole object. The borrow keeps the provenance of the object, so the class's
+/// `from_base` conversions can get back to it. The borrow must be emitted in an unsafe context.
+pub fn borrow_base(this: TokenStream, fields: TokenStream, mutable: bool) -> TokenStream {
+    if mutable {
+        quote!(&mut *::core::ptr::addr_of_mut!((*(#this as *mut Self))#fields))
+    } else {
+        quote!(&*::core::ptr::addr_of!((*(#this as *const Self))#fields))
+    }
+}
+
+/// Makes an identifier that only generated code can name, so that it can't clash with the names
+/// the user chooses.
+pub fn make_hygienic_ident(name: &str) -> Ident {
+    Ident::new(name, Span::mixed_site())
+}
+
+/// Replaces each `crate::` path in `tokens` with `$crate::`, so the tokens can be emitted inside
+/// exported macros and still resolve from other crates. Paths that already start with `$crate` are
+/// left alone.
+pub fn replace_crate_paths(tokens: TokenStream) -> TokenStream {
+    let mut tokens = tokens.into_iter().peekable();
+    let mut output = TokenStream::new();
+    let mut after_dollar = false;
+    while let Some(token) = tokens.next() {
+        let is_dollar = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == '$');
+        match token {
+            TokenTree::Ident(ident)
+                if ident == "crate"
+                    && !after_dollar
+                    && matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == ':') =>
+            {
+                output.extend(quote!($#ident));
+            }
+            TokenTree::Group(group) => {
+                let mut new_group =
+                    Group::new(group.delimiter(), replace_crate_paths(group.stream()));
+                new_group.set_span(group.span());
+                output.extend(new_group.into_token_stream());
+            }
+            token => output.extend(token.into_token_stream()),
+        }
+        after_dollar = is_dollar;
+    }
+
+    output
+}
+
+pub fn last_segment(path: &Path) -> &PathSegment {
+    path.segments.last().expect("expected path segments")
+}
+
+pub fn last_segment_mut(path: &mut Path) -> &mut PathSegment {
+    path.segments.last_mut().expect("expected path segments")
+}
+
+/// Removes a field from a punctuation.
+pub fn remove_punctuated<T: Clone, P: Default, F: FnMut(&T) -> bool>(
+    punct: &Punctuated<T, P>,
+    mut pred: F,
+) -> Punctuated<T, P> {
+    let mut new_punct = Punctuated::new();
+    for item in punct {
+        if pred(item) {
+            new_punct.push(item.clone());
+        }
+    }
+    new_punct
+}